@@ -0,0 +1,413 @@
+// Lets other people watch a game from another terminal, by sending them everything that gets drawn.
+// Every game that can be watched uses this, with its own magic so that spectators can't mix them up.
+//
+// Anyone who starts watching gets a keyframe with the whole screen first, and after that only the cells that changed.
+// A cell is whatever the game draws with, like a tile or a pixel, and they all take up the same number of bytes.
+// If a spectator can't keep up, they skip ahead to a fresh keyframe instead of getting everything they missed.
+//
+// Games don't always draw, like while they're waiting on a menu, so spectators get sent a keepalive
+// whenever nothing's been sent for a while. That way a quiet game doesn't look like one that's gone.
+
+use std::{
+    io::{self, BufReader, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+// Bumped whenever the frames change, so mismatched versions don't try to watch each other.
+pub const PROTOCOL_VERSION: u16 = 2;
+
+// The first byte of every frame, saying which kind it is.
+const KEYFRAME: u8 = 0;
+const DELTA: u8 = 1;
+const KEEPALIVE: u8 = 2;
+
+// How many frames can be waiting to go out to a spectator before they count as falling behind.
+const BACKLOG: usize = 4;
+
+// How often the server checks for new spectators.
+const ACCEPT_TIME: Duration = Duration::from_millis(100);
+
+// How long a spectator can go without being sent anything before they get a keepalive.
+pub const KEEPALIVE_TIME: Duration = Duration::from_secs(1);
+
+// The biggest screen a spectator will take, so a broken or hostile game can't get them to allocate a huge frame.
+// It's far more than any terminal needs, even with every cell being a few pixels.
+pub const MAX_WIDTH: usize = 4096;
+pub const MAX_HEIGHT: usize = 4096;
+pub const MAX_FRAME_BYTES: usize = 32 * 1024 * 1024;
+
+// How long a spectator waits to hear anything before deciding the game is gone.
+// It's a lot longer than `KEEPALIVE_TIME`, so only a game that's really gone hits it.
+pub const WATCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Someone watching, and the thread sending them frames.
+struct Spectator {
+    frames: SyncSender<Arc<Vec<u8>>>,
+    // Set when they've missed a frame, and so need a keyframe next.
+    behind: bool,
+}
+
+// Sends every frame to anyone who connects to it.
+pub struct Server {
+    joined: Receiver<Spectator>,
+    spectators: Vec<Spectator>,
+
+    // The last frame that was sent, which the next one gets compared against.
+    previous: Vec<u8>,
+    previous_size: (usize, usize),
+
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl Server {
+    // Starts listening for spectators on a port, who can only watch from this computer.
+    // Everyone who connects gets sent `magic` first.
+    pub fn start(port: u16, magic: [u8; 4]) -> io::Result<Server> {
+        Server::start_on(SocketAddr::from((Ipv4Addr::LOCALHOST, port)), magic)
+    }
+
+    // Starts listening for spectators on a specific address, like `0.0.0.0:7778` to let anyone on the network watch.
+    pub fn start_on(addr: SocketAddr, magic: [u8; 4]) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        let addr = listener.local_addr()?;
+        let (sender, joined) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        // Someone who hangs up straight away just gets ignored.
+                        if let Ok(spectator) = greet(stream, magic) {
+                            if sender.send(spectator).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(_) => thread::sleep(ACCEPT_TIME),
+                }
+            }
+        });
+
+        Ok(Server {
+            joined,
+            spectators: Vec::new(),
+            previous: Vec::new(),
+            previous_size: (0, 0),
+            addr,
+            stop,
+        })
+    }
+
+    // The port the server ended up on, which is handy when it was started on port 0.
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    // Sends a frame to every spectator. The cells go row by row.
+    pub fn broadcast(&mut self, width: usize, height: usize, cells: &[u8], status: &str) {
+        self.spectators.extend(self.joined.try_iter());
+
+        if self.spectators.is_empty() {
+            return;
+        }
+
+        let cell_size = cells.len() / (width * height).max(1);
+        let resized = self.previous_size != (width, height);
+
+        // A keyframe is the whole screen, so one only gets made when someone new or someone who's behind needs it.
+        let keyframe = (resized || self.spectators.iter().any(|spectator| spectator.behind))
+            .then(|| Arc::new(keyframe(width, height, cell_size, cells, status)));
+        let delta = match &keyframe {
+            Some(keyframe) if resized => Arc::clone(keyframe),
+            _ => Arc::new(delta(&self.previous, cells, cell_size, status)),
+        };
+
+        self.spectators.retain_mut(|spectator| {
+            let frame = match &keyframe {
+                Some(keyframe) if spectator.behind => Arc::clone(keyframe),
+                _ => Arc::clone(&delta),
+            };
+
+            match spectator.frames.try_send(frame) {
+                Ok(()) => {
+                    spectator.behind = false;
+                    true
+                }
+                Err(TrySendError::Full(_)) => {
+                    spectator.behind = true;
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+
+        self.previous = cells.to_vec();
+        self.previous_size = (width, height);
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Says hello to a new spectator, and starts a thread that sends them frames, or keepalives while there aren't any.
+// The thread stops once the server's gone, or the spectator hangs up.
+fn greet(mut stream: TcpStream, magic: [u8; 4]) -> io::Result<Spectator> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    stream.write_all(&magic)?;
+    stream.write_all(&PROTOCOL_VERSION.to_be_bytes())?;
+
+    let (frames, receiver) = mpsc::sync_channel::<Arc<Vec<u8>>>(BACKLOG);
+
+    thread::spawn(move || loop {
+        let sent = match receiver.recv_timeout(KEEPALIVE_TIME) {
+            Ok(frame) => stream.write_all(&frame),
+            Err(RecvTimeoutError::Timeout) => stream.write_all(&[KEEPALIVE]),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        if sent.is_err() {
+            break;
+        }
+    });
+
+    Ok(Spectator {
+        frames,
+        behind: true,
+    })
+}
+
+fn push_status(bytes: &mut Vec<u8>, status: &str) {
+    let status = &status.as_bytes()[..status.len().min(u16::MAX as usize)];
+
+    bytes.extend_from_slice(&(status.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(status);
+}
+
+fn keyframe(width: usize, height: usize, cell_size: usize, cells: &[u8], status: &str) -> Vec<u8> {
+    let mut bytes = vec![KEYFRAME];
+
+    bytes.extend_from_slice(&(width as u16).to_be_bytes());
+    bytes.extend_from_slice(&(height as u16).to_be_bytes());
+    bytes.push(cell_size as u8);
+    push_status(&mut bytes, status);
+    bytes.extend_from_slice(cells);
+
+    bytes
+}
+
+// Only the cells that changed since the last frame get sent, in runs of cells next to each other.
+fn delta(previous: &[u8], cells: &[u8], cell_size: usize, status: &str) -> Vec<u8> {
+    let mut runs = Vec::new();
+    let mut count: u32 = 0;
+    let mut start = None;
+
+    let changed = |i: usize| {
+        cells[i * cell_size..(i + 1) * cell_size] != previous[i * cell_size..(i + 1) * cell_size]
+    };
+    let total = cells.len() / cell_size.max(1);
+
+    for i in 0..=total {
+        let in_run =
+            i < total && changed(i) && start.is_none_or(|start| i - start < u16::MAX as usize);
+
+        match (start, in_run) {
+            (None, true) => start = Some(i),
+            (Some(run_start), false) => {
+                runs.extend_from_slice(&(run_start as u32).to_be_bytes());
+                runs.extend_from_slice(&((i - run_start) as u16).to_be_bytes());
+                runs.extend_from_slice(&cells[run_start * cell_size..i * cell_size]);
+                count += 1;
+
+                start = if i < total && changed(i) {
+                    Some(i)
+                } else {
+                    None
+                };
+            }
+            _ => (),
+        }
+    }
+
+    let mut bytes = vec![DELTA];
+
+    push_status(&mut bytes, status);
+    bytes.extend_from_slice(&count.to_be_bytes());
+    bytes.extend_from_slice(&runs);
+
+    bytes
+}
+
+// What the screen being watched looks like.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: usize,
+    pub cells: Vec<u8>,
+    pub status: String,
+}
+
+impl Frame {
+    // The bytes of the cell at a position.
+    pub fn cell(&self, x: usize, y: usize) -> &[u8] {
+        let start = (y * self.width + x) * self.cell_size;
+
+        &self.cells[start..start + self.cell_size]
+    }
+}
+
+// Receives frames from a server, and keeps track of what the screen looks like.
+pub struct Watcher {
+    reader: BufReader<TcpStream>,
+    pub frame: Frame,
+}
+
+impl Watcher {
+    // Connects to a server, which has to be sending the same `magic`.
+    pub fn connect(addr: &str, magic: [u8; 4]) -> io::Result<Watcher> {
+        let stream = TcpStream::connect(addr)?;
+
+        stream.set_read_timeout(Some(WATCH_TIMEOUT))?;
+
+        let mut reader = BufReader::new(stream);
+        let mut greeting = [0; 4];
+        reader.read_exact(&mut greeting)?;
+
+        if greeting != magic {
+            return Err(invalid("that isn't broadcasting the right game"));
+        }
+
+        let version = read_u16(&mut reader)?;
+
+        if version != PROTOCOL_VERSION {
+            return Err(invalid(&format!(
+                "the game is on version {version}, but this is version {PROTOCOL_VERSION}"
+            )));
+        }
+
+        Ok(Watcher {
+            reader,
+            frame: Frame::default(),
+        })
+    }
+
+    // Blocks until something arrives from the server, and gives back whether the screen changed.
+    // Keepalives don't change anything, they just mean the game's still there.
+    pub fn next_frame(&mut self) -> io::Result<bool> {
+        let mut kind = [0; 1];
+        self.reader.read_exact(&mut kind)?;
+
+        let frame = &mut self.frame;
+
+        match kind[0] {
+            KEYFRAME => {
+                let width = read_u16(&mut self.reader)? as usize;
+                let height = read_u16(&mut self.reader)? as usize;
+
+                let mut cell_size = [0; 1];
+                self.reader.read_exact(&mut cell_size)?;
+                let cell_size = cell_size[0] as usize;
+
+                let size = width
+                    .checked_mul(height)
+                    .and_then(|cells| cells.checked_mul(cell_size))
+                    .filter(|&size| {
+                        width <= MAX_WIDTH && height <= MAX_HEIGHT && size <= MAX_FRAME_BYTES
+                    })
+                    .ok_or_else(|| {
+                        invalid(&format!(
+                            "the game sent a {width}x{height} screen, which is too big to watch"
+                        ))
+                    })?;
+
+                frame.width = width;
+                frame.height = height;
+                frame.cell_size = cell_size;
+                frame.status = read_status(&mut self.reader)?;
+                frame.cells = vec![0; size];
+                self.reader.read_exact(&mut frame.cells)?;
+            }
+            DELTA => {
+                frame.status = read_status(&mut self.reader)?;
+
+                for _ in 0..read_u32(&mut self.reader)? {
+                    let start = read_u32(&mut self.reader)? as usize * frame.cell_size;
+                    let length = read_u16(&mut self.reader)? as usize * frame.cell_size;
+
+                    match frame.cells.get_mut(start..start + length) {
+                        Some(run) => self.reader.read_exact(run)?,
+                        None => return Err(invalid("a frame changed cells that aren't there")),
+                    }
+                }
+            }
+            KEEPALIVE => return Ok(false),
+            kind => return Err(invalid(&format!("unknown frame kind {kind}"))),
+        }
+
+        Ok(true)
+    }
+
+    // Keeps reading frames in a separate thread, and hands every new one over as it comes in.
+    // Reading blocks, so this is what lets a spectator quit without waiting on the next frame.
+    // The last thing sent is whatever went wrong, which is an `UnexpectedEof` when the game just closed.
+    pub fn spawn(mut self) -> Receiver<io::Result<Frame>> {
+        let (sender, frames) = mpsc::channel();
+
+        thread::spawn(move || loop {
+            let update = match self.next_frame() {
+                Ok(false) => continue,
+                Ok(true) => Ok(self.frame.clone()),
+                Err(err) => Err(err),
+            };
+            let failed = update.is_err();
+
+            // Nobody's watching anymore.
+            if sender.send(update).is_err() || failed {
+                break;
+            }
+        });
+
+        frames
+    }
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u16::from_be_bytes(bytes))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_status(reader: &mut impl Read) -> io::Result<String> {
+    let mut status = vec![0; read_u16(reader)? as usize];
+    reader.read_exact(&mut status)?;
+
+    Ok(String::from_utf8_lossy(&status).into_owned())
+}
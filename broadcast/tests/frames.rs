@@ -0,0 +1,130 @@
+use std::{
+    io::{ErrorKind, Write},
+    net::TcpListener,
+    thread,
+    time::{Duration, Instant},
+};
+
+use broadcast::{Server, Watcher, KEEPALIVE_TIME, PROTOCOL_VERSION, WATCH_TIMEOUT};
+
+const MAGIC: [u8; 4] = *b"TEST";
+
+// Starts a server on any free port, with someone watching it.
+fn watched() -> (Server, Watcher) {
+    let server = Server::start(0, MAGIC).unwrap();
+    let watcher = Watcher::connect(&format!("127.0.0.1:{}", server.port()), MAGIC).unwrap();
+
+    // The server only hands over new spectators after it's greeted them, which happens in its own thread.
+    thread::sleep(Duration::from_millis(200));
+
+    (server, watcher)
+}
+
+#[test]
+fn keyframes_and_deltas_rebuild_the_screen() {
+    let (mut server, mut watcher) = watched();
+
+    let mut cells: Vec<u8> = (0..4 * 3 * 2).collect();
+    server.broadcast(4, 3, &cells, "first");
+
+    assert!(watcher.next_frame().unwrap());
+    assert_eq!(watcher.frame.width, 4);
+    assert_eq!(watcher.frame.height, 3);
+    assert_eq!(watcher.frame.cell_size, 2);
+    assert_eq!(watcher.frame.cells, cells);
+    assert_eq!(watcher.frame.status, "first");
+
+    // A couple of separate runs of changes, one of them at the very end.
+    cells[2] = 100;
+    cells[3] = 101;
+    cells[22] = 102;
+    cells[23] = 103;
+    server.broadcast(4, 3, &cells, "second");
+
+    assert!(watcher.next_frame().unwrap());
+    assert_eq!(watcher.frame.cells, cells);
+    assert_eq!(watcher.frame.cell(1, 0), [100, 101]);
+    assert_eq!(watcher.frame.cell(3, 2), [102, 103]);
+    assert_eq!(watcher.frame.status, "second");
+}
+
+#[test]
+fn quiet_games_send_keepalives() {
+    let (_server, mut watcher) = watched();
+    let start = Instant::now();
+
+    // Nothing gets broadcast, but the spectator still hears from the game well before they'd give up on it.
+    assert!(!watcher.next_frame().unwrap());
+    assert!(start.elapsed() < WATCH_TIMEOUT);
+    assert!(start.elapsed() >= KEEPALIVE_TIME / 2);
+}
+
+#[test]
+fn other_games_are_turned_away() {
+    let server = Server::start(0, MAGIC).unwrap();
+
+    assert!(Watcher::connect(&format!("127.0.0.1:{}", server.port()), *b"NOPE").is_err());
+}
+
+#[test]
+fn spectators_find_out_when_the_game_closes() {
+    let (mut server, watcher) = watched();
+    let frames = watcher.spawn();
+
+    server.broadcast(1, 1, &[7], "");
+
+    assert_eq!(frames.recv().unwrap().unwrap().cells, [7]);
+
+    drop(server);
+
+    assert_eq!(
+        frames.recv().unwrap().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn late_spectators_get_the_whole_screen() {
+    let (mut server, mut first) = watched();
+
+    server.broadcast(2, 1, &[1, 2], "");
+    assert!(first.next_frame().unwrap());
+
+    let mut late = Watcher::connect(&format!("127.0.0.1:{}", server.port()), MAGIC).unwrap();
+    thread::sleep(Duration::from_millis(200));
+
+    // Only the second cell changes, but the late spectator has never seen the first one.
+    server.broadcast(2, 1, &[1, 3], "");
+
+    assert!(first.next_frame().unwrap());
+    assert!(late.next_frame().unwrap());
+    assert_eq!(first.frame.cells, [1, 3]);
+    assert_eq!(late.frame.cells, [1, 3]);
+}
+
+#[test]
+fn huge_screens_are_refused() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // Pretends to be a game sending a keyframe that'd take about a terabyte to hold.
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        stream.write_all(&MAGIC).unwrap();
+        stream.write_all(&PROTOCOL_VERSION.to_be_bytes()).unwrap();
+        stream
+            .write_all(&[0, 0xff, 0xff, 0xff, 0xff, 0xff])
+            .unwrap();
+
+        // Stays open so the spectator doesn't just see the game hang up.
+        thread::sleep(Duration::from_secs(1));
+    });
+
+    let mut watcher = Watcher::connect(&addr.to_string(), MAGIC).unwrap();
+
+    assert_eq!(
+        watcher.next_frame().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
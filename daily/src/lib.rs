@@ -0,0 +1,54 @@
+// The daily challenge, where everyone playing a game on the same day gets the same seed and so the same game.
+// Every game and the launcher go through here, so they can't disagree about what today's seed is.
+//
+// Days are counted in UTC, so the challenge ticks over at the same moment for everyone, whatever time zone they're in.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY_SECONDS: u64 = 24 * 60 * 60;
+
+// Which day it was at some time, counted in whole days since 1970.
+pub fn day_at(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() / DAY_SECONDS)
+        .unwrap_or(0)
+}
+
+// Which day it is today, counted in whole days since 1970.
+pub fn today() -> u64 {
+    day_at(SystemTime::now())
+}
+
+// The seed every game is played from on a day's challenge.
+// It's just the day itself, since the games' random number generators mix it up plenty.
+pub fn seed(day: u64) -> u64 {
+    day
+}
+
+// Today's seed, which is what `--daily` plays.
+pub fn today_seed() -> u64 {
+    seed(today())
+}
+
+// Turns a day since 1970 into a date like `2024-03-09`.
+// This is the usual way of counting through 400 year eras, which keeps leap years simple.
+pub fn date(day: u64) -> String {
+    let shifted = day + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+
+    // Months are counted from March here, so February's leap day ends up at the end of the year.
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
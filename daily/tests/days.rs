@@ -0,0 +1,65 @@
+// Checks that the daily challenge's seed only changes at midnight UTC, and that days turn into the right dates.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use daily::{date, day_at, seed};
+
+// A time some number of seconds into a day since 1970.
+fn time(day: u64, seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(day * 24 * 60 * 60 + seconds)
+}
+
+#[test]
+fn the_seed_lasts_all_day() {
+    // That's 2024-03-09.
+    let day = 19_791;
+    let morning = seed(day_at(time(day, 0)));
+
+    assert_eq!(morning, seed(day_at(time(day, 12 * 60 * 60))));
+    assert_eq!(morning, seed(day_at(time(day, 24 * 60 * 60 - 1))));
+    assert_ne!(morning, seed(day_at(time(day, 24 * 60 * 60))));
+    assert_ne!(morning, seed(day_at(time(day, 0) - Duration::from_secs(1))));
+}
+
+#[test]
+fn every_day_gets_its_own_seed() {
+    let seeds: Vec<u64> = (0..1000).map(seed).collect();
+
+    for (i, seed) in seeds.iter().enumerate() {
+        assert!(!seeds[i + 1..].contains(seed), "day {i} shares its seed");
+    }
+}
+
+#[test]
+fn days_turn_into_dates() {
+    assert_eq!(date(0), "1970-01-01");
+    assert_eq!(date(19_791), "2024-03-09");
+    assert_eq!(date(day_at(time(19_791, 23 * 60 * 60))), "2024-03-09");
+
+    // Leap days, including 2000, which is one even though it's a century.
+    assert_eq!(date(11_016), "2000-02-29");
+    assert_eq!(date(11_017), "2000-03-01");
+    assert_eq!(date(19_782), "2024-02-29");
+    assert_eq!(date(19_722), "2023-12-31");
+    assert_eq!(date(19_723), "2024-01-01");
+}
+
+#[test]
+fn times_before_1970_count_as_day_0() {
+    assert_eq!(day_at(UNIX_EPOCH - Duration::from_secs(1)), 0);
+}
+
+#[test]
+fn games_and_the_launcher_agree_on_today() {
+    // Games started on their own use `today_seed`, and the launcher hands them `seed(today())`.
+    // It's checked again if midnight went by in between.
+    loop {
+        let day = daily::today();
+        let seeds = (daily::today_seed(), seed(day));
+
+        if daily::today() == day {
+            assert_eq!(seeds.0, seeds.1);
+            break;
+        }
+    }
+}
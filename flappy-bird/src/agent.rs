@@ -0,0 +1,139 @@
+// Lets a bot play the game, by turning it into an environment that gets stepped along one action at a time.
+
+use crate::{
+    game::{Game, Pipes, State},
+    GROUND_HEIGHT, PLAYER_SPAWN_X,
+};
+
+// How many pipes ahead of the bird an observation looks at.
+pub const PIPES_AHEAD: usize = 2;
+
+// How many numbers an observation gets turned into.
+pub const INPUTS: usize = 2 + PIPES_AHEAD * 3;
+
+// The rewards for staying alive for another tick, getting past a pipe and crashing.
+pub const ALIVE_REWARD: f32 = 0.1;
+pub const PIPE_REWARD: f32 = 1.0;
+pub const CRASH_REWARD: f32 = -1.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Nothing,
+    Flap,
+}
+
+// How a pipe looks from where the bird is.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PipeView {
+    // How many columns there are between the bird's beak and the front of the pipe, as a fraction of the screen's width.
+    // It's 0 while the bird is going through the pipe.
+    pub distance: f32,
+
+    // How far above and below the bird the top and bottom of the gap are, as a fraction of the screen's height.
+    // The top is negative while it's above the bird, and the bottom is positive while it's below.
+    pub gap_top: f32,
+    pub gap_bottom: f32,
+}
+
+// Everything a bot gets to know about the game, in numbers that stay between about -1 and 1 on any screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Observation {
+    // How far down the screen the bird is.
+    pub y: f32,
+
+    // How fast the bird is going, in screens per tick.
+    pub velocity: f32,
+
+    // The next pipes the bird has to get through, from the closest one.
+    pub pipes: [PipeView; PIPES_AHEAD],
+}
+
+impl Observation {
+    pub fn to_inputs(&self) -> [f32; INPUTS] {
+        let mut inputs = [0.0; INPUTS];
+
+        inputs[0] = self.y;
+        inputs[1] = self.velocity;
+
+        for (i, pipe) in self.pipes.iter().enumerate() {
+            inputs[2 + i * 3] = pipe.distance;
+            inputs[3 + i * 3] = pipe.gap_top;
+            inputs[4 + i * 3] = pipe.gap_bottom;
+        }
+
+        inputs
+    }
+}
+
+impl Game {
+    // Starts a new game from a seed, skipping straight past the start screen.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        *self = Game::with_size(self.width, self.height, seed);
+        self.state = State::Playing;
+
+        self.observation()
+    }
+
+    // Does something and moves the game along a tick, giving back what the bot sees afterwards,
+    // what it got for it, and whether the game's over.
+    pub fn step(&mut self, action: Action) -> (Observation, f32, bool) {
+        if self.state != State::Playing {
+            return (self.observation(), 0.0, true);
+        }
+
+        if action == Action::Flap {
+            self.flap();
+        }
+
+        let score = self.score;
+        self.tick();
+
+        let done = self.state != State::Playing;
+        let reward = if done {
+            CRASH_REWARD
+        } else {
+            ALIVE_REWARD + (self.score - score) as f32 * PIPE_REWARD
+        };
+
+        (self.observation(), reward, done)
+    }
+
+    pub fn observation(&self) -> Observation {
+        let height = self.height as f32;
+        let tail = self.player_rect().from_x;
+
+        // The pipes the bird hasn't made it all the way past yet.
+        let mut ahead = self
+            .pipes
+            .iter()
+            .filter(|pipe| pipe.pos_x + pipe.width() > tail);
+
+        let pipes = [(); PIPES_AHEAD].map(|_| match ahead.next() {
+            Some(pipe) => self.view(pipe),
+
+            // With no pipe coming up, it looks like one that's miles away with the whole sky as its gap.
+            None => PipeView {
+                distance: 1.0,
+                gap_top: -self.player_y / height,
+                gap_bottom: (self.height - GROUND_HEIGHT) as f32 / height - self.player_y / height,
+            },
+        });
+
+        Observation {
+            y: self.player_y / height,
+            velocity: self.velocity / height,
+            pipes,
+        }
+    }
+
+    fn view(&self, pipe: &Pipes) -> PipeView {
+        let height = self.height as f32;
+        let [top, bottom] = pipe.rects(self.height);
+
+        PipeView {
+            distance: pipe.pos_x.saturating_sub(PLAYER_SPAWN_X) as f32 / self.width as f32,
+            gap_top: (top.to_y as f32 - self.player_y) / height,
+            gap_bottom: (bottom.from_y as f32 - self.player_y) / height,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Makes up the scenery behind the pipes, with each layer further back scrolling by slower.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    screen::{BgColor, FgColor, Layer, Tile},
+    GROUND_HEIGHT,
+};
+
+// How fast each layer scrolls compared to the pipes.
+pub const CLOUD_SPEED: f32 = 0.1;
+pub const SKYLINE_SPEED: f32 = 0.3;
+pub const GROUND_SPEED: f32 = 1.0;
+
+// How many columns of sky there are for every cloud.
+pub const CLOUD_SPACING: usize = 12;
+
+// The tallest a building can be. They never take up more than a third of the sky either.
+pub const MAX_BUILDING_HEIGHT: usize = 8;
+
+// How many columns the stripes on top of the ground repeat every.
+pub const STRIPE_WIDTH: usize = 4;
+
+// Makes every layer for a screen, from the back to the front.
+// The scenery comes from the game's seed, so a daily challenge looks the same for everyone too.
+pub fn layers(width: usize, height: usize, seed: u64) -> Vec<Layer> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    vec![
+        clouds(&mut rng, width, height),
+        skyline(&mut rng, width, height),
+        ground(&mut rng, width, height),
+    ]
+}
+
+// Clouds float around in the top third of the sky, and are a row taller in the middle.
+fn clouds(rng: &mut impl Rng, width: usize, height: usize) -> Layer {
+    let rows = (height / 3).max(2);
+    let mut layer = Layer::new(1, width, rows, CLOUD_SPEED);
+    let cloud = || Tile::new(FgColor::White, BgColor::White, b' ');
+
+    for _ in 0..(width / CLOUD_SPACING).max(1) {
+        let x = rng.gen_range(0..width.max(1));
+        let y = rng.gen_range(0..rows - 1);
+        let length = rng.gen_range(4..10);
+
+        for column in 0..length {
+            if column > 0 && column < length - 1 {
+                layer.set(x + column, y, cloud());
+            }
+
+            layer.set(x + column, y + 1, cloud());
+        }
+    }
+
+    layer
+}
+
+// A row of buildings standing on the ground, with the odd window lit up.
+fn skyline(rng: &mut impl Rng, width: usize, height: usize) -> Layer {
+    let ground = height.saturating_sub(GROUND_HEIGHT);
+    let rows = MAX_BUILDING_HEIGHT.min(ground / 3).max(1);
+    let mut layer = Layer::new(ground.saturating_sub(rows), width, rows, SKYLINE_SPEED);
+
+    // Buildings go from left to right until the pattern is full, so the last one butts up against the first.
+    let mut x = 0;
+
+    while x < width {
+        let building_width = rng.gen_range(3..8).min(width - x);
+        let building_height = rng.gen_range(1..=rows);
+
+        for column in x..x + building_width {
+            for y in rows - building_height..rows {
+                let tile = if rng.gen_bool(0.15) {
+                    Tile::new(FgColor::Yellow, BgColor::Cyan, b':')
+                } else {
+                    Tile::new(FgColor::Black, BgColor::Cyan, b' ')
+                };
+
+                layer.set(column, y, tile);
+            }
+        }
+
+        x += building_width + rng.gen_range(0..3);
+    }
+
+    layer
+}
+
+// Stripes of grass along the top of the ground, and dirt with pebbles in it underneath.
+fn ground(rng: &mut impl Rng, width: usize, height: usize) -> Layer {
+    // The pattern has to be a whole number of stripes long so that it lines up with itself.
+    let width = width.div_ceil(STRIPE_WIDTH) * STRIPE_WIDTH;
+    let mut layer = Layer::new(
+        height.saturating_sub(GROUND_HEIGHT),
+        width,
+        GROUND_HEIGHT,
+        GROUND_SPEED,
+    );
+
+    for x in 0..width {
+        let grass = if x % STRIPE_WIDTH < STRIPE_WIDTH / 2 {
+            b'/'
+        } else {
+            b' '
+        };
+
+        layer.set(x, 0, Tile::new(FgColor::Black, BgColor::Green, grass));
+
+        for y in 1..GROUND_HEIGHT {
+            let dirt = if rng.gen_bool(0.1) { b'.' } else { b'=' };
+
+            layer.set(x, y, Tile::new(FgColor::Black, BgColor::Yellow, dirt));
+        }
+    }
+
+    layer
+}
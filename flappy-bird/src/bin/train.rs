@@ -0,0 +1,224 @@
+// Evolves bots for flappy bird with a genetic algorithm, playing lots of games at once without drawing any of them.
+// Whenever a generation's best bot does better than any before it, it gets saved, so it can be watched with `--bot`.
+//
+// Usage: train [--generations <n>] [--population <n>] [--games <n>] [--seed <n>] [--out <file>]
+
+use std::{env, process::exit, str::FromStr, thread};
+
+use flappy_bird::{bot::Brain, game::Game};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// The size of the screen the bots train on.
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// Games get cut off after this many ticks, so that a bot that's good enough doesn't play forever.
+const MAX_TICKS: u32 = 5000;
+
+// How many of the best bots make it into the next generation untouched, as a fraction of the population.
+const ELITE: f32 = 0.1;
+
+// How many bots get picked at random when choosing a parent, with the best of them winning.
+const TOURNAMENT: usize = 3;
+
+// How likely each weight is to mutate, and by how much.
+const MUTATION_RATE: f64 = 0.1;
+const MUTATION_AMOUNT: f32 = 0.5;
+
+// How many games every generation's best bot plays to see if it's the best one yet.
+// These are the same every generation, so bots from different ones can be compared fairly.
+const CHECK_GAMES: u64 = 20;
+
+struct Settings {
+    generations: u32,
+    population: usize,
+    games: usize,
+    seed: u64,
+    out: String,
+}
+
+// How well a bot did over some games.
+#[derive(Clone, Copy, Default)]
+struct Fitness {
+    reward: f32,
+    best_score: u32,
+}
+
+fn main() {
+    let mut settings = Settings {
+        generations: 50,
+        population: 100,
+        games: 5,
+        seed: 1,
+        out: String::from("bot.txt"),
+    };
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let value = args.next();
+
+        match arg.as_str() {
+            "--generations" => settings.generations = number(&arg, value),
+            "--population" => settings.population = number::<usize>(&arg, value).max(2),
+            "--games" => settings.games = number::<usize>(&arg, value).max(1),
+            "--seed" => settings.seed = number(&arg, value),
+            "--out" => match value {
+                Some(path) => settings.out = path,
+                None => usage(&format!("{arg} needs a file, like bot.txt")),
+            },
+            _ => usage(&format!("Unknown argument: {arg}")),
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut population: Vec<Brain> = (0..settings.population)
+        .map(|_| Brain::random(&mut rng))
+        .collect();
+
+    let check_seeds: Vec<u64> = (0..CHECK_GAMES)
+        .map(|game| settings.seed.wrapping_add(game))
+        .collect();
+    let mut best = Fitness {
+        reward: f32::NEG_INFINITY,
+        best_score: 0,
+    };
+
+    for generation in 1..=settings.generations {
+        // Everyone plays the same games, which are different every generation so nothing gets learned off by heart.
+        let seeds: Vec<u64> = (0..settings.games).map(|_| rng.gen()).collect();
+        let fitness = evaluate(&population, &seeds);
+
+        let mut ranked: Vec<usize> = (0..population.len()).collect();
+        ranked.sort_by(|a, b| fitness[*b].reward.total_cmp(&fitness[*a].reward));
+
+        let champion = &population[ranked[0]];
+        let checked = evaluate(std::slice::from_ref(champion), &check_seeds)[0];
+        let average =
+            fitness.iter().map(|fitness| fitness.reward).sum::<f32>() / fitness.len() as f32;
+
+        let mut line = format!(
+            "Generation {generation}: best {:.1}, average {average:.1}, top score {}",
+            fitness[ranked[0]].reward, fitness[ranked[0]].best_score,
+        );
+
+        if checked.reward > best.reward {
+            best = checked;
+
+            match champion.save(&settings.out) {
+                Ok(()) => line += &format!(" (saved, scoring up to {})", checked.best_score),
+                Err(err) => line += &format!(" (couldn't save it: {err})"),
+            }
+        }
+
+        println!("{line}");
+
+        population = breed(&population, &ranked, &mut rng);
+    }
+
+    println!(
+        "The best bot got an average reward of {:.1} and scored up to {}. It's saved in {}.",
+        best.reward, best.best_score, settings.out
+    );
+}
+
+// Plays a game until the bot crashes or runs out of time.
+fn play(brain: &Brain, seed: u64) -> (f32, u32) {
+    let mut game = Game::with_size(WIDTH, HEIGHT, seed);
+    let mut observation = game.reset(seed);
+    let mut total = 0.0;
+
+    for _ in 0..MAX_TICKS {
+        let (next, reward, done) = game.step(brain.decide(&observation));
+
+        observation = next;
+        total += reward;
+
+        if done {
+            break;
+        }
+    }
+
+    (total, game.score)
+}
+
+// Has every bot play every game, spreading the bots out over as many threads as there are cores.
+fn evaluate(population: &[Brain], seeds: &[u64]) -> Vec<Fitness> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk = population.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = population
+            .chunks(chunk)
+            .map(|brains| {
+                scope.spawn(move || {
+                    brains
+                        .iter()
+                        .map(|brain| {
+                            let mut fitness = Fitness::default();
+
+                            for seed in seeds {
+                                let (reward, score) = play(brain, *seed);
+
+                                fitness.reward += reward / seeds.len() as f32;
+                                fitness.best_score = fitness.best_score.max(score);
+                            }
+
+                            fitness
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+// Makes the next generation, keeping the best bots as they are and filling the rest up with their children.
+fn breed(population: &[Brain], ranked: &[usize], rng: &mut StdRng) -> Vec<Brain> {
+    let elite = ((population.len() as f32 * ELITE) as usize).max(1);
+    let mut next: Vec<Brain> = ranked[..elite]
+        .iter()
+        .map(|index| population[*index].clone())
+        .collect();
+
+    // A bot's place in the ranking is all that matters when picking parents, with the lowest place winning.
+    let pick = |rng: &mut StdRng| {
+        let place = (0..TOURNAMENT)
+            .map(|_| rng.gen_range(0..ranked.len()))
+            .min()
+            .unwrap();
+
+        &population[ranked[place]]
+    };
+
+    while next.len() < population.len() {
+        let mother = pick(rng);
+        let father = pick(rng);
+
+        let mut child = mother.crossover(father, rng);
+        child.mutate(rng, MUTATION_RATE, MUTATION_AMOUNT);
+
+        next.push(child);
+    }
+
+    next
+}
+
+fn number<T: FromStr>(arg: &str, value: Option<String>) -> T {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(number)) => number,
+        _ => usage(&format!("{arg} needs a number")),
+    }
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("{message}");
+    eprintln!("Usage: train [--generations <n>] [--population <n>] [--games <n>] [--seed <n>] [--out <file>]");
+
+    exit(1);
+}
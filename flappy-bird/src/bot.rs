@@ -0,0 +1,98 @@
+// A tiny neural network that decides when to flap, which gets evolved by the trainer and can be saved to a file.
+
+use std::{fs, io};
+
+use rand::Rng;
+
+use crate::agent::{Action, Observation, INPUTS};
+
+// How many neurons are in the hidden layer.
+pub const HIDDEN: usize = 6;
+
+// How many weights a brain has, counting a bias for every neuron.
+pub const WEIGHTS: usize = (INPUTS + 1) * HIDDEN + HIDDEN + 1;
+
+// A network with one hidden layer and a single output, which flaps whenever it's above 0.
+// The weights are the genome that gets evolved.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Brain {
+    pub weights: Vec<f32>,
+}
+
+impl Brain {
+    pub fn random(rng: &mut impl Rng) -> Brain {
+        Brain {
+            weights: (0..WEIGHTS).map(|_| rng.gen_range(-1.0..1.0)).collect(),
+        }
+    }
+
+    pub fn decide(&self, observation: &Observation) -> Action {
+        let inputs = observation.to_inputs();
+        let (hidden_weights, output_weights) = self.weights.split_at((INPUTS + 1) * HIDDEN);
+
+        // Every hidden neuron has a weight for each input, followed by its bias.
+        let mut output = output_weights[HIDDEN];
+
+        for (neuron, weights) in hidden_weights.chunks(INPUTS + 1).enumerate() {
+            let sum: f32 = inputs
+                .iter()
+                .zip(weights)
+                .map(|(input, weight)| input * weight)
+                .sum::<f32>()
+                + weights[INPUTS];
+
+            output += sum.tanh() * output_weights[neuron];
+        }
+
+        if output > 0.0 {
+            Action::Flap
+        } else {
+            Action::Nothing
+        }
+    }
+
+    // Takes each weight from one parent or the other.
+    pub fn crossover(&self, other: &Brain, rng: &mut impl Rng) -> Brain {
+        Brain {
+            weights: self
+                .weights
+                .iter()
+                .zip(&other.weights)
+                .map(|(a, b)| if rng.gen() { *a } else { *b })
+                .collect(),
+        }
+    }
+
+    // Nudges some of the weights by up to `amount` either way, with each one having a `rate` chance.
+    pub fn mutate(&mut self, rng: &mut impl Rng, rate: f64, amount: f32) {
+        for weight in &mut self.weights {
+            if rng.gen_bool(rate) {
+                *weight += rng.gen_range(-amount..amount);
+            }
+        }
+    }
+
+    // Brains are saved as their weights, all on one line.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let weights: Vec<String> = self.weights.iter().map(f32::to_string).collect();
+
+        fs::write(path, weights.join(" ") + "\n")
+    }
+
+    pub fn load(path: &str) -> io::Result<Brain> {
+        let weights = fs::read_to_string(path)?
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if weights.len() != WEIGHTS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected {WEIGHTS} weights, found {}", weights.len()),
+            ));
+        }
+
+        Ok(Brain { weights })
+    }
+}
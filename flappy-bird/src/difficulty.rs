@@ -0,0 +1,88 @@
+// How the game gets harder as the score goes up.
+
+use crate::{FLAP_VELOCITY, GRAVITY, PIPE_GAP, TERMINAL_VELOCITY};
+
+// The smallest the gap in a pipe gets, in rows either side of its middle.
+pub const MIN_PIPE_GAP: usize = 3;
+
+// How many points it takes for the gap to shrink by another row.
+pub const GAP_SHRINK_SCORE: u32 = 10;
+
+// How many columns apart pipes are at the start, and how close together they end up.
+pub const PIPE_SPACING: usize = 20;
+pub const MIN_PIPE_SPACING: usize = 12;
+
+// How many points it takes for pipes to get another column closer together.
+pub const SPACING_SHRINK_SCORE: u32 = 3;
+
+// How many columns the pipes move every tick at the start, how much faster that gets with every point, and the fastest it gets.
+pub const SCROLL_SPEED: f32 = 1.0;
+pub const SCROLL_SPEED_UP: f32 = 0.02;
+pub const MAX_SCROLL_SPEED: f32 = 1.5;
+
+// The score where moving and wide pipes start showing up, and how likely they are once they do.
+pub const MOVING_PIPE_SCORE: u32 = 5;
+pub const MOVING_PIPE_CHANCE: f64 = 0.3;
+pub const WIDE_PIPE_SCORE: u32 = 10;
+pub const WIDE_PIPE_CHANCE: f64 = 0.2;
+
+// How many rows the bird climbs every tick when it keeps on flapping.
+pub const CLIMB_SPEED: f32 = -(FLAP_VELOCITY + GRAVITY);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Difficulty {
+    // How many rows there are either side of the middle of a pipe's gap.
+    pub gap: usize,
+
+    // How many columns there are from the front of one pipe to the front of the next one.
+    pub spacing: usize,
+
+    // How many columns the pipes move every tick.
+    pub speed: f32,
+
+    pub moving_chance: f64,
+    pub wide_chance: f64,
+}
+
+impl Difficulty {
+    pub fn new(score: u32) -> Difficulty {
+        let shrunk = |score_per_step: u32| (score / score_per_step) as usize;
+
+        Difficulty {
+            gap: PIPE_GAP
+                .saturating_sub(shrunk(GAP_SHRINK_SCORE))
+                .max(MIN_PIPE_GAP),
+            spacing: PIPE_SPACING
+                .saturating_sub(shrunk(SPACING_SHRINK_SCORE))
+                .max(MIN_PIPE_SPACING),
+            speed: (SCROLL_SPEED + SCROLL_SPEED_UP * score as f32).min(MAX_SCROLL_SPEED),
+            moving_chance: if score >= MOVING_PIPE_SCORE {
+                MOVING_PIPE_CHANCE
+            } else {
+                0.0
+            },
+            wide_chance: if score >= WIDE_PIPE_SCORE {
+                WIDE_PIPE_CHANCE
+            } else {
+                0.0
+            },
+        }
+    }
+
+    // How many rows the bird can be sure to climb or fall while crossing `columns` columns, starting from a standstill.
+    pub fn reach(&self, columns: usize) -> usize {
+        let ticks = (columns as f32 / self.speed) as u32;
+
+        let mut velocity: f32 = 0.0;
+        let mut fallen = 0.0;
+
+        for _ in 0..ticks {
+            velocity = (velocity + GRAVITY).min(TERMINAL_VELOCITY);
+            fallen += velocity;
+        }
+
+        let climbed = ticks as f32 * CLIMB_SPEED;
+
+        fallen.min(climbed) as usize
+    }
+}
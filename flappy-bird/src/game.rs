@@ -1,90 +1,581 @@
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::{exit, screen, PIPE_GAP, PIPE_WIDTH, PLAYER_SPAWN_X};
+use crate::{
+    difficulty::{Difficulty, SCROLL_SPEED},
+    screen::{self, BgColor},
+    BOB_SPEED, BRONZE_SCORE, FLAP_VELOCITY, GOLD_SCORE, GRAVITY, GROUND_HEIGHT, PIPE_MOVE_RANGE,
+    PIPE_MOVE_TICKS, PIPE_WIDTH, PLATINUM_SCORE, PLAYER_LENGTH, PLAYER_SPAWN_X, PLAYER_SPAWN_Y,
+    RETRY_DELAY, SILVER_SCORE, TERMINAL_VELOCITY,
+};
+
+// Where a game is at, from waiting to start all the way to being over.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum State {
+    // The bird bobs up and down until the first flap.
+    Ready,
+    Playing,
+    // The bird has crashed, and is falling down to the ground.
+    Dying,
+    // The bird is on the ground and the score is shown, until the player goes again.
+    GameOver,
+}
+
+// What a game gets you, going by the score.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl Medal {
+    pub fn new(score: u32) -> Option<Medal> {
+        match score {
+            PLATINUM_SCORE.. => Some(Medal::Platinum),
+            GOLD_SCORE.. => Some(Medal::Gold),
+            SILVER_SCORE.. => Some(Medal::Silver),
+            BRONZE_SCORE.. => Some(Medal::Bronze),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Medal::Bronze => "Bronze",
+            Medal::Silver => "Silver",
+            Medal::Gold => "Gold",
+            Medal::Platinum => "Platinum",
+        }
+    }
+
+    pub fn color(&self) -> BgColor {
+        match self {
+            Medal::Bronze => BgColor::Red,
+            Medal::Silver => BgColor::White,
+            Medal::Gold => BgColor::Yellow,
+            Medal::Platinum => BgColor::Cyan,
+        }
+    }
+}
+
+// A block of tiles, going from the first corner up to but not including the second one, just like `Screen::rectangle`.
+// Both drawing and collisions work off of these, so what you see is always what you hit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rect {
+    pub from_x: usize,
+    pub from_y: usize,
+    pub to_x: usize,
+    pub to_y: usize,
+}
+
+impl Rect {
+    pub fn is_empty(&self) -> bool {
+        self.from_x >= self.to_x || self.from_y >= self.to_y
+    }
+
+    // Whether any tile is in both rectangles.
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.from_x < other.to_x
+            && other.from_x < self.to_x
+            && self.from_y < other.to_y
+            && other.from_y < self.to_y
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PipeKind {
+    Normal,
+    // The gap moves up and down while the pipe goes by.
+    Moving,
+    // Twice as wide as a normal pipe, so the bird has to stay in the gap for longer.
+    Wide,
+}
+
+impl PipeKind {
+    pub fn width(&self) -> usize {
+        match self {
+            PipeKind::Wide => PIPE_WIDTH * 2,
+            _ => PIPE_WIDTH,
+        }
+    }
+
+    // How many rows the gap moves away from the middle, either way.
+    pub fn amplitude(&self) -> usize {
+        match self {
+            PipeKind::Moving => PIPE_MOVE_RANGE,
+            _ => 0,
+        }
+    }
+}
 
 pub struct Pipes {
+    pub kind: PipeKind,
     pub pos_x: usize,
+
+    // The middle of the gap right now.
     pub offset_y: usize,
+
+    // Where the gap moves around, which is always the same as `offset_y` unless the pipe is moving.
+    pub center_y: usize,
+
+    // How many rows there are either side of `offset_y`.
+    pub gap: usize,
+
     pub scored: bool,
+
+    // How many ticks the pipe has been around for, which is what moving pipes go off of.
+    age: u32,
 }
 
 impl Pipes {
-    pub fn new(game: &Game) -> Pipes {
-        let offset: usize = rand::thread_rng().gen_range(5..game.height - 5);
-
+    pub fn new(kind: PipeKind, pos_x: usize, center_y: usize, gap: usize) -> Pipes {
         Pipes {
-            pos_x: game.width - 3,
-            offset_y: offset,
+            kind,
+            pos_x,
+            offset_y: center_y,
+            center_y,
+            gap,
             scored: false,
+            age: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.kind.width()
+    }
+
+    // Moves the gap along if it's a moving pipe, going from the middle, up, down to the bottom and back again.
+    pub fn tick(&mut self) {
+        let range = self.kind.amplitude();
+
+        if range == 0 {
+            return;
         }
+
+        self.age += 1;
+
+        let step = (self.age / PIPE_MOVE_TICKS) as usize;
+        let phase = (step + range) % (range * 4);
+
+        self.offset_y = if phase < range * 2 {
+            (self.center_y + phase).saturating_sub(range)
+        } else {
+            (self.center_y + range * 3).saturating_sub(phase)
+        };
+    }
+
+    // The top and bottom half of the pipe, on a screen that's `height` rows tall.
+    // The gap is the rows `gap` either side of `offset_y`, not counting the first row of the bottom half.
+    pub fn rects(&self, height: usize) -> [Rect; 2] {
+        let to_x = self.pos_x + self.width();
+
+        [
+            Rect {
+                from_x: self.pos_x,
+                from_y: 0,
+                to_x,
+                to_y: self.offset_y.saturating_sub(self.gap).min(height),
+            },
+            Rect {
+                from_x: self.pos_x,
+                from_y: (self.offset_y + self.gap).min(height),
+                to_x,
+                to_y: height,
+            },
+        ]
     }
 }
 
+// A recording of a run, which can be raced against by playing the same seed again.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ghost {
+    pub seed: u64,
+    pub score: u32,
+
+    // Where the bird was after every tick, from the first flap until it hit the ground.
+    pub path: Vec<f32>,
+}
+
 pub struct Game {
-    pub player_y: usize,
+    // The bird's height, in rows from the top of the screen. It's fractional so the bird can move smoothly.
+    pub player_y: f32,
+
+    // How many rows the bird moves down every tick, which is negative while it's going up.
+    pub velocity: f32,
+
     pub score: u32,
     pub pipes: Vec<Pipes>,
 
     pub width: usize,
     pub height: usize,
 
-    flap: u8,
-    spawn_pipe_timer: u8,
+    pub state: State,
+
+    // How many ticks the game has been in its current state for.
+    pub timer: u32,
+
+    // The best score out of every game played on this screen, which carries over when retrying.
+    pub best: u32,
+
+    // How far the pipes have moved towards the next column, since they can move a fraction of one every tick.
+    scroll: f32,
+
+    // Where every random thing in the game comes from, so the same seed always plays out the same way.
+    rng: StdRng,
+
+    // How many columns everything has scrolled along since the game started, which the background goes off of.
+    pub scrolled: usize,
+
+    // What the pipes come from, which is what lets a run be played again.
+    pub seed: u64,
+
+    // Where the bird has been so far in this run.
+    pub path: Vec<f32>,
+
+    // How many ticks it's been since the first flap.
+    pub frame: usize,
+
+    // The best run so far, which carries over when retrying just like `best`.
+    pub ghost: Option<Ghost>,
+
+    // Whether this is a daily challenge, which plays the same pipes every time instead of new ones.
+    pub daily: bool,
 }
 
 impl Game {
-    pub fn new(screen: &screen::Screen) -> Game {
+    // Given a seed, every retry plays the same pipes as the first game, which is how the daily challenge works.
+    pub fn new(screen: &screen::Screen, seed: Option<u64>) -> Game {
+        let mut game = Game::with_size(
+            screen.width,
+            screen.height,
+            seed.unwrap_or_else(|| rand::thread_rng().gen()),
+        );
+
+        game.daily = seed.is_some();
+        game
+    }
+
+    // Makes a game that isn't tied to a screen, which is handy for playing without drawing anything.
+    pub fn with_size(width: usize, height: usize, seed: u64) -> Game {
         Game {
-            player_y: 10,
-            flap: 0,
-            spawn_pipe_timer: 0,
-            width: screen.width,
-            height: screen.height,
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            path: Vec::new(),
+            frame: 0,
+            ghost: None,
+            daily: false,
+            player_y: PLAYER_SPAWN_Y,
+            velocity: 0.0,
+            scroll: 0.0,
+            scrolled: 0,
+            width,
+            height,
+            state: State::Ready,
+            timer: 0,
+            best: 0,
             score: 0,
             pipes: vec![],
         }
     }
 
+    // Starts over from the beginning with new pipes, only keeping the best score and run.
+    // The daily challenge always has the same pipes though.
+    pub fn retry(&mut self) {
+        let seed = if self.daily {
+            self.seed
+        } else {
+            self.rng.gen()
+        };
+
+        self.restart(seed);
+    }
+
+    // Starts over with the same pipes as the best run, so it can be raced against.
+    pub fn race(&mut self) {
+        if let Some(seed) = self.ghost.as_ref().map(|ghost| ghost.seed) {
+            self.restart(seed);
+        }
+    }
+
+    fn restart(&mut self, seed: u64) {
+        let best = self.best;
+        let ghost = self.ghost.take();
+        let daily = self.daily;
+
+        *self = Game::with_size(self.width, self.height, seed);
+        self.best = best;
+        self.ghost = ghost;
+        self.daily = daily;
+    }
+
+    // Whether this game is a race against the best run.
+    pub fn racing(&self) -> bool {
+        self.ghost
+            .as_ref()
+            .is_some_and(|ghost| ghost.seed == self.seed)
+    }
+
+    // Where the best run's bird was at this point, if it's being raced and hasn't landed yet.
+    pub fn ghost_y(&self) -> Option<f32> {
+        if !self.racing() || self.frame == 0 {
+            return None;
+        }
+
+        self.ghost.as_ref()?.path.get(self.frame - 1).copied()
+    }
+
+    fn set_state(&mut self, state: State) {
+        self.state = state;
+        self.timer = 0;
+    }
+
+    // Flapping sends the bird upwards straight away, however fast it was falling.
+    // It also starts the game, and once it's over, starts a new one.
     pub fn flap(&mut self) {
-        self.flap = 2;
+        match self.state {
+            State::Ready => {
+                self.set_state(State::Playing);
+                self.velocity = FLAP_VELOCITY;
+            }
+            State::Playing => self.velocity = FLAP_VELOCITY,
+            State::Dying => (),
+            State::GameOver => {
+                if self.timer >= RETRY_DELAY {
+                    self.retry();
+                }
+            }
+        }
     }
 
-    pub fn tick(&mut self) {
-        for pipe in &mut self.pipes {
-            pipe.pos_x -= 1;
+    pub fn medal(&self) -> Option<Medal> {
+        Medal::new(self.score)
+    }
+
+    // The row the bird is in.
+    pub fn player_row(&self) -> usize {
+        self.player_y.max(0.0) as usize
+    }
+
+    // The tiles the bird is drawn over.
+    pub fn player_rect(&self) -> Rect {
+        let row = self.player_row();
+
+        Rect {
+            from_x: (PLAYER_SPAWN_X + 1).saturating_sub(PLAYER_LENGTH),
+            from_y: row,
+            to_x: PLAYER_SPAWN_X + 1,
+            to_y: row + 1,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::new(self.score)
+    }
+
+    // Pipes come in this far from the right of the screen, which leaves enough room for even a wide pipe.
+    fn spawn_x(&self) -> usize {
+        self.width.saturating_sub(PipeKind::Wide.width() + 1)
+    }
+
+    // Where the middle of a new pipe's gap can go, so that it's on the screen and the bird can reach it from the last pipe.
+    // Gives back `None` if there's nowhere it could go.
+    fn gap_range(&self, kind: PipeKind, difficulty: &Difficulty) -> Option<(usize, usize)> {
+        let gap = difficulty.gap;
+        let amplitude = kind.amplitude();
+
+        // There has to be at least a row of pipe on either side of the gap, even once it's moved.
+        let mut from = gap + amplitude + 1;
+        let mut to = self
+            .height
+            .checked_sub(GROUND_HEIGHT + 1 + gap + amplitude)?;
+
+        if let Some(last) = self.pipes.last() {
+            // How many columns the bird has to change height in, between the back of its tail leaving the last pipe
+            // and its beak getting to the new one.
+            // One more column is taken off since the pipes can move more than one column at a time.
+            let columns = self
+                .spawn_x()
+                .saturating_sub(last.pos_x + last.width() + PLAYER_LENGTH);
+
+            // The bird can go through the very edge of both gaps if it has to, and has to be ready for either pipe
+            // to have moved as far away as it can.
+            let slack = (last.gap + gap).saturating_sub(2);
+            let shift = (difficulty.reach(columns) + slack)
+                .checked_sub(last.kind.amplitude() + amplitude)?;
+
+            from = from.max(last.center_y.saturating_sub(shift));
+            to = to.min(last.center_y + shift);
         }
 
-        if self.flap > 0 {
-            self.player_y -= 1;
-            self.flap -= 1;
+        if from <= to {
+            Some((from, to))
         } else {
-            self.player_y += 1;
+            None
         }
+    }
 
-        self.pipes.retain(|pipe| pipe.pos_x > 0);
+    // Adds a new pipe on the right, once the last one is far enough away.
+    fn spawn_pipe(&mut self, difficulty: &Difficulty) {
+        let spawn_x = self.spawn_x();
 
-        if self.spawn_pipe_timer <= 0 {
-            self.spawn_pipe_timer = 20;
+        if let Some(last) = self.pipes.last() {
+            if last.pos_x + difficulty.spacing > spawn_x {
+                return;
+            }
+        }
 
-            self.pipes.push(Pipes::new(&self))
+        let kind = if self.rng.gen_bool(difficulty.wide_chance) {
+            PipeKind::Wide
+        } else if self.rng.gen_bool(difficulty.moving_chance) {
+            PipeKind::Moving
         } else {
-            self.spawn_pipe_timer -= 1;
+            PipeKind::Normal
+        };
+
+        // Moving pipes need more room, so a normal one is used instead if there isn't any.
+        let (kind, range) = match self.gap_range(kind, difficulty) {
+            Some(range) => (kind, Some(range)),
+            None => (
+                PipeKind::Normal,
+                self.gap_range(PipeKind::Normal, difficulty),
+            ),
+        };
+
+        // This only happens when the screen is too small to fit a gap in at all, and then nothing is reachable anyways.
+        let center = match range {
+            Some((from, to)) => self.rng.gen_range(from..=to),
+            None => self.height / 2,
+        };
+
+        self.pipes
+            .push(Pipes::new(kind, spawn_x, center, difficulty.gap));
+    }
+
+    pub fn ground(&self) -> Rect {
+        Rect {
+            from_x: 0,
+            from_y: self.height.saturating_sub(GROUND_HEIGHT),
+            to_x: self.width,
+            to_y: self.height,
+        }
+    }
+
+    // Whether the bird has run into a pipe or the ground, or gone off of the screen.
+    pub fn collides(&self) -> bool {
+        let player = self.player_rect();
+
+        self.player_y < 0.0
+            || player.to_y > self.height
+            || player.overlaps(&self.ground())
+            || self
+                .pipes
+                .iter()
+                .flat_map(|pipe| pipe.rects(self.height))
+                .any(|rect| player.overlaps(&rect))
+    }
+
+    pub fn tick(&mut self) {
+        self.timer += 1;
+
+        if self.state != State::Ready {
+            self.frame += 1;
+        }
+
+        match self.state {
+            State::Ready => {
+                // Bobbing up and down by half a row, which is just enough to show it's alive.
+                self.player_y = PLAYER_SPAWN_Y + (self.timer as f32 * BOB_SPEED).sin() * 0.5;
+
+                // The ground keeps going by, but there aren't any pipes yet.
+                self.scroll(SCROLL_SPEED);
+            }
+            State::Playing => self.tick_playing(),
+            State::Dying => self.tick_dying(),
+            State::GameOver => (),
+        }
+
+        if self.state == State::Playing || self.state == State::Dying {
+            self.path.push(self.player_y);
+        }
+    }
+
+    // Moves everything along by `speed` columns, giving back how many whole columns that came to.
+    fn scroll(&mut self, speed: f32) -> usize {
+        self.scroll += speed;
+
+        let columns = self.scroll as usize;
+        self.scroll -= columns as f32;
+        self.scrolled += columns;
+
+        columns
+    }
+
+    fn tick_playing(&mut self) {
+        let difficulty = self.difficulty();
+
+        let columns = self.scroll(difficulty.speed);
+
+        // Pipes go once they'd move off the screen.
+        self.pipes.retain(|pipe| pipe.pos_x >= columns);
+
+        for pipe in &mut self.pipes {
+            pipe.pos_x -= columns;
+            pipe.tick();
+        }
+
+        self.velocity = (self.velocity + GRAVITY).min(TERMINAL_VELOCITY);
+        self.player_y += self.velocity;
+
+        self.spawn_pipe(&difficulty);
+
+        if self.collides() {
+            self.set_state(State::Dying);
+            self.velocity = 0.0;
+
+            return;
         }
 
-        let pipe = &mut self.pipes[0];
+        // A pipe counts once the bird's beak is past the front of it.
+        for pipe in &mut self.pipes {
+            if PLAYER_SPAWN_X > pipe.pos_x && !pipe.scored {
+                self.score += 1;
 
-        if ((PLAYER_SPAWN_X >= pipe.pos_x && PLAYER_SPAWN_X <= pipe.pos_x + PIPE_WIDTH)
-            && (self.player_y >= pipe.offset_y + PIPE_GAP
-                || self.player_y <= pipe.offset_y - PIPE_GAP - 1))
-            || (self.player_y == 0 || self.player_y == self.height)
-        {
-            exit(&format!("Game Over! You had a score of: {}", self.score))
+                pipe.scored = true;
+            }
         }
+    }
+
+    // The bird drops straight through anything in its way, until it lands on the ground.
+    fn tick_dying(&mut self) {
+        self.velocity = (self.velocity + GRAVITY).min(TERMINAL_VELOCITY);
+        self.player_y += self.velocity;
+
+        // Landing leaves the bird sitting on the ground, in the bottom half of the row above it.
+        let landed = self.ground().from_y.saturating_sub(1) as f32 + 0.5;
+
+        if self.player_y >= landed {
+            self.player_y = landed;
+            self.velocity = 0.0;
+            self.path.push(self.player_y);
+            self.best = self.best.max(self.score);
 
-        if PLAYER_SPAWN_X >= pipe.pos_x + 1 && !pipe.scored {
-            self.score += 1;
+            // Beating the best run, or getting the first one in, makes this the one to race.
+            if self
+                .ghost
+                .as_ref()
+                .is_none_or(|ghost| self.score > ghost.score)
+            {
+                self.ghost = Some(Ghost {
+                    seed: self.seed,
+                    score: self.score,
+                    path: self.path.clone(),
+                });
+            }
 
-            pipe.scored = true;
+            self.set_state(State::GameOver);
         }
     }
 }
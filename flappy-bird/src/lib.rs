@@ -1,17 +1,56 @@
+pub mod agent;
+pub mod background;
+pub mod bot;
+pub mod difficulty;
 pub mod game;
+pub mod play;
 pub mod screen;
 
+pub use play::{run, watch};
+
 use std::time::Duration;
 
+// How many rows there are either side of the middle of a pipe's gap, before the game starts getting harder.
 pub const PIPE_GAP: usize = 4;
 pub const PIPE_WIDTH: usize = 2;
+
+// How many rows a moving pipe's gap goes up and down by, and how many ticks it takes to move a row.
+pub const PIPE_MOVE_RANGE: usize = 2;
+pub const PIPE_MOVE_TICKS: u32 = 2;
 pub const PLAYER_SPAWN_X: usize = 5;
+pub const PLAYER_SPAWN_Y: f32 = 10.0;
 
-pub const RENDER_TIME: Duration = Duration::from_millis(10);
-pub const TICK_TIME: Duration = Duration::from_millis(100);
+// How fast the bird bobs up and down before the game starts, in radians per tick.
+pub const BOB_SPEED: f32 = 0.6;
+
+// How many tiles long the bird is, ending with its beak at `PLAYER_SPAWN_X`.
+pub const PLAYER_LENGTH: usize = 3;
+
+// How many rows of ground there are at the bottom of the screen.
+pub const GROUND_HEIGHT: usize = 2;
 
-pub fn exit(exit_message: &str) {
-    println!("\r\x1b[2J\r\x1b[H\x1b[?25h{exit_message}");
+// How much faster the bird falls every tick, in rows per tick.
+pub const GRAVITY: f32 = 0.35;
+// The fastest the bird can fall.
+pub const TERMINAL_VELOCITY: f32 = 1.5;
+// How fast the bird goes up straight after flapping. It's negative since rows count down the screen.
+pub const FLAP_VELOCITY: f32 = -1.6;
 
-    std::process::exit(0)
-}
+// How many ticks the screen flashes for when the bird crashes.
+pub const FLASH_TICKS: u32 = 2;
+
+// How many ticks have to go by after a game's over before flapping starts a new one,
+// so that flapping away when the bird crashes doesn't skip straight past the score.
+pub const RETRY_DELAY: u32 = 5;
+
+// How many ticks a bot being watched waits before going again, so its score can be read.
+pub const BOT_RETRY_DELAY: u32 = 20;
+
+// The scores it takes to get each medal.
+pub const BRONZE_SCORE: u32 = 10;
+pub const SILVER_SCORE: u32 = 20;
+pub const GOLD_SCORE: u32 = 30;
+pub const PLATINUM_SCORE: u32 = 40;
+
+pub const RENDER_TIME: Duration = Duration::from_millis(10);
+pub const TICK_TIME: Duration = Duration::from_millis(100);
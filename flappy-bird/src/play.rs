@@ -0,0 +1,264 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use console::{Key, Term};
+use session::{spawn_input, ChannelMessage, EndReason, GameResult};
+
+use crate::{
+    agent::Action,
+    background,
+    bot::Brain,
+    game::{Game, State},
+    screen::{self, BgColor, FgColor, Screen, Tile},
+    BOT_RETRY_DELAY, FLASH_TICKS, PLAYER_SPAWN_X, RENDER_TIME, TICK_TIME,
+};
+
+// Keeps playing games until the player quits, and returns the best score out of all of them.
+// This is the entry point for anything that wants to host flappy bird, like a launcher.
+// With a `seed`, every game has the same pipes, like for a daily challenge.
+pub fn run(term: &Term, seed: Option<u64>) -> GameResult {
+    session(term, None, seed)
+}
+
+// Lets a bot play instead, going again by itself every time it crashes, until `q` is pressed.
+pub fn watch(term: &Term, bot: &Brain) -> GameResult {
+    session(term, Some(bot), None)
+}
+
+fn session(term: &Term, bot: Option<&Brain>, seed: Option<u64>) -> GameResult {
+    let (keys, input_thread) = spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    let result = play(Screen::new(term), &keys, bot, seed);
+
+    input_thread.join().unwrap();
+
+    result
+}
+
+// Writes out a line of text in the middle of the screen.
+fn centered(screen: &mut Screen, y: usize, text: &str, bg_color: BgColor) {
+    screen.text(
+        screen.width.saturating_sub(text.len()) / 2,
+        y,
+        text,
+        FgColor::Black,
+        bg_color,
+    );
+}
+
+// Draws whatever the game is up to, and gives back what should go in the status bar.
+pub fn draw(screen: &mut Screen, game: &Game) -> String {
+    screen.reset();
+
+    // The screen flashes for a moment when the bird crashes.
+    if game.state == State::Dying && game.timer < FLASH_TICKS {
+        screen.bg_color(Tile::new(FgColor::Black, BgColor::White, b' '));
+
+        return format!("Score: {}", game.score);
+    }
+
+    screen.draw_layers(game.scrolled);
+    draw_pipes(screen, game);
+    draw_ghost(screen, game);
+    draw_player(screen, game);
+
+    let middle = screen.height / 2;
+
+    match game.state {
+        State::Ready => {
+            centered(screen, middle, "Press Up to start", BgColor::Yellow);
+
+            match &game.ghost {
+                Some(ghost) if game.racing() => {
+                    format!("Racing your best run, which scored {}", ghost.score)
+                }
+                _ => format!("Best: {}", game.best),
+            }
+        }
+        State::Playing | State::Dying => format!("Score: {}", game.score),
+        State::GameOver => {
+            let score = format!("Score: {}", game.score);
+            let best = format!("Best: {}", game.best);
+
+            centered(
+                screen,
+                middle.saturating_sub(3),
+                "Game Over!",
+                BgColor::Yellow,
+            );
+            centered(screen, middle.saturating_sub(1), &score, BgColor::Yellow);
+            centered(screen, middle, &best, BgColor::Yellow);
+
+            if let Some(medal) = game.medal() {
+                let medal_text = format!("{} medal", medal.to_str());
+
+                centered(screen, middle + 2, &medal_text, medal.color());
+            }
+
+            String::from("Press Up or r to play again, g to race your best run, or q to quit.")
+        }
+    }
+}
+
+// Draws every pipe, using the same rectangles that the bird collides with.
+pub fn draw_pipes(screen: &mut Screen, game: &Game) {
+    for pipe in &game.pipes {
+        for rect in pipe.rects(screen.height) {
+            screen.rectangle(
+                rect.from_x,
+                rect.from_y,
+                rect.to_x,
+                rect.to_y,
+                Tile::new(FgColor::Black, BgColor::Green, b'@'),
+            );
+        }
+    }
+}
+
+// The bird only fills half of its row, so it can be drawn twice as precisely as a whole tile allows.
+fn half_block(y: f32) -> u8 {
+    if y.fract() < 0.5 {
+        screen::UPPER_HALF
+    } else {
+        screen::LOWER_HALF
+    }
+}
+
+pub fn draw_player(screen: &mut Screen, game: &Game) {
+    let rect = game.player_rect();
+    let glyph = half_block(game.player_y);
+
+    for x in rect.from_x..rect.to_x {
+        // The last tile is the beak.
+        let color = if x == PLAYER_SPAWN_X {
+            FgColor::Red
+        } else {
+            FgColor::Yellow
+        };
+
+        screen.set(x, rect.from_y, Tile::new(color, BgColor::Blue, glyph));
+    }
+}
+
+// Draws the best run's bird in white over whatever's behind it, without hiding any of it.
+pub fn draw_ghost(screen: &mut Screen, game: &Game) {
+    let Some(y) = game.ghost_y() else {
+        return;
+    };
+
+    let row = y.max(0.0) as usize;
+
+    if row >= screen.height {
+        return;
+    }
+
+    let rect = game.player_rect();
+
+    for x in rect.from_x..rect.to_x {
+        screen.overlay(x, row, FgColor::White, half_block(y));
+    }
+}
+
+// Plays games until the player quits, and gives back the best score along with how that run ended.
+fn play(
+    mut screen: Screen,
+    keys: &Receiver<Key>,
+    bot: Option<&Brain>,
+    seed: Option<u64>,
+) -> GameResult {
+    let game = Game::new(&screen, seed);
+
+    for layer in background::layers(screen.width, screen.height, game.seed) {
+        screen.add_layer(layer);
+    }
+
+    let game = Arc::new(Mutex::new(game));
+
+    // Renders the game every 10 ms, until it gets told to stop.
+    let (sender, receiver) = mpsc::channel();
+    let game_mutex = Arc::clone(&game);
+    let watching = bot.is_some();
+    let render_thread = thread::spawn(move || loop {
+        thread::sleep(RENDER_TIME);
+
+        let game = game_mutex.lock().unwrap();
+
+        if let Ok(msg) = receiver.try_recv() {
+            match msg {
+                ChannelMessage::Stop => return,
+            }
+        }
+
+        let status = draw(&mut screen, &game);
+
+        if watching {
+            screen.render(&format!("{status} | Watching a bot, press q to quit."));
+        } else {
+            screen.render(&status);
+        }
+    });
+
+    // The game loop, it handles any input that came in and then ticks every 100 ms.
+    loop {
+        thread::sleep(TICK_TIME);
+
+        let mut game = game.lock().unwrap();
+        let mut quit = false;
+
+        for key in keys.try_iter() {
+            match key {
+                Key::ArrowUp => game.flap(),
+                Key::Char('r') if game.state == State::GameOver => game.retry(),
+                Key::Char('g') if game.state == State::GameOver => game.race(),
+                Key::Char('q') => quit = true,
+                _ => (),
+            }
+        }
+
+        if quit {
+            // The render thread is told to stop while the game is still locked, so it never draws after this.
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break;
+        }
+
+        if let Some(bot) = bot {
+            match game.state {
+                State::Playing => {
+                    if bot.decide(&game.observation()) == Action::Flap {
+                        game.flap();
+                    }
+                }
+                // The score stays up for a little while before the bot goes again.
+                State::GameOver if game.timer < BOT_RETRY_DELAY => (),
+                _ => game.flap(),
+            }
+        }
+
+        game.tick();
+    }
+
+    render_thread.join().unwrap();
+    let game = game.lock().unwrap();
+
+    // Quitting halfway through a run still counts it, if it beat every run that crashed.
+    // `best` only covers runs that crashed, and there's a ghost as soon as one has.
+    let reason = match game.state {
+        State::Ready | State::Playing if game.score > game.best || game.ghost.is_none() => {
+            EndReason::Quit
+        }
+        _ => EndReason::GameOver,
+    };
+
+    GameResult {
+        score: game.best.max(game.score),
+        reason,
+    }
+}
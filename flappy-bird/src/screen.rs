@@ -1,13 +1,20 @@
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    slice,
+};
 
 use console::Term;
 
+use crate::GROUND_HEIGHT;
+
 pub enum FgColor {
     Black,
     Red,
     Green,
     Yellow,
     Blue,
+    Cyan,
+    White,
     Default,
 }
 
@@ -17,6 +24,8 @@ pub enum BgColor {
     Green,
     Yellow,
     Blue,
+    Cyan,
+    White,
     Default,
 }
 
@@ -28,6 +37,8 @@ impl FgColor {
             FgColor::Green => *b"32",
             FgColor::Yellow => *b"33",
             FgColor::Blue => *b"34",
+            FgColor::Cyan => *b"36",
+            FgColor::White => *b"37",
             FgColor::Default => *b"39",
         }
     }
@@ -41,11 +52,27 @@ impl BgColor {
             BgColor::Green => *b"42",
             BgColor::Yellow => *b"43",
             BgColor::Blue => *b"44",
+            BgColor::Cyan => *b"46",
+            BgColor::White => *b"47",
             BgColor::Default => *b"49",
         }
     }
 }
 
+// Glyphs that take up more than one byte, which get stored in a tile as one of these codes instead.
+// Any other byte is printed out as it is.
+pub const UPPER_HALF: u8 = 0x80;
+pub const LOWER_HALF: u8 = 0x81;
+
+// The bytes that actually get printed for a tile's glyph.
+fn glyph(code: &u8) -> &[u8] {
+    match *code {
+        UPPER_HALF => "▀".as_bytes(),
+        LOWER_HALF => "▄".as_bytes(),
+        _ => slice::from_ref(code),
+    }
+}
+
 pub struct Tile {
     pub fg_color: FgColor,
     pub bg_color: BgColor,
@@ -72,6 +99,43 @@ impl Tile {
     }
 }
 
+// A strip of the background that repeats itself all the way across the screen, and scrolls along at its own speed.
+pub struct Layer {
+    // The top row of the layer.
+    pub y: usize,
+
+    // How many columns the layer moves for every column that the pipes move.
+    pub speed: f32,
+
+    // How often the layer repeats itself.
+    pub width: usize,
+
+    // The rows of tiles, where `None` lets whatever's behind show through.
+    tiles: Vec<Vec<Option<[u8; 11]>>>,
+}
+
+impl Layer {
+    // Makes an empty layer, that repeats every `width` columns.
+    pub fn new(y: usize, width: usize, height: usize, speed: f32) -> Layer {
+        let width = width.max(1);
+
+        Layer {
+            y,
+            speed,
+            width,
+            tiles: vec![vec![None; width]; height],
+        }
+    }
+
+    // Sets a tile, counting from the top left of the layer.
+    // Anything past the right edge wraps back around to the left, so the pattern always lines up with itself.
+    pub fn set(&mut self, x: usize, y: usize, tile: Tile) {
+        if let Some(row) = self.tiles.get_mut(y) {
+            row[x % self.width] = Some(tile.calc());
+        }
+    }
+}
+
 pub struct Screen {
     pub height: usize,
     pub width: usize,
@@ -84,6 +148,9 @@ pub struct Screen {
     // You can wipe the screen with the reset() method.
     initial: Vec<Vec<[u8; 11]>>,
 
+    // The background layers, from the back to the front.
+    layers: Vec<Layer>,
+
     stdout: std::io::Stdout,
 }
 
@@ -92,9 +159,11 @@ impl Screen {
         // Get the terminal dimentions.
         let (height, width) = term.size();
 
-        let height: usize = (height - 1).into();
-        let width: usize = width.into();
+        Screen::with_size(width.into(), (height - 1).into())
+    }
 
+    // Makes a screen that isn't tied to the size of a terminal.
+    pub fn with_size(width: usize, height: usize) -> Screen {
         let initial = Vec::new();
         let data: Vec<Vec<[u8; 11]>> = Vec::new();
 
@@ -103,6 +172,7 @@ impl Screen {
             height,
             width,
             initial,
+            layers: Vec::new(),
             stdout: io::stdout(),
         };
 
@@ -110,20 +180,16 @@ impl Screen {
 
         screen.initial = screen.data.clone();
 
-        return screen;
+        screen
     }
 
     // Draws the initial background elements like the sky and ground.
     pub fn initial_draw(&mut self) {
         self.bg_color(Tile::new(FgColor::Black, BgColor::Blue, b'-'));
-        self.line(
-            self.height - 2,
-            Tile::new(FgColor::Black, BgColor::Yellow, b'='),
-        );
-        self.line(
-            self.height - 1,
-            Tile::new(FgColor::Black, BgColor::Yellow, b'='),
-        );
+
+        for y in self.height.saturating_sub(GROUND_HEIGHT)..self.height {
+            self.line(y, Tile::new(FgColor::Black, BgColor::Yellow, b'='));
+        }
     }
 
     pub fn render(&self, status: &str) {
@@ -142,7 +208,8 @@ impl Screen {
         // Iterate over every single tile and print it out.
         for line in &self.data {
             for tile in line {
-                lock.write_all(tile).unwrap();
+                lock.write_all(&tile[..10]).unwrap();
+                lock.write_all(glyph(&tile[10])).unwrap();
             }
         }
 
@@ -161,6 +228,44 @@ impl Screen {
         self.data = self.initial.clone();
     }
 
+    // Changes the glyph and foreground color of a tile while keeping its background, so whatever's behind shows through.
+    pub fn overlay(&mut self, x: usize, y: usize, fg_color: FgColor, glyph: u8) {
+        let tile = &mut self.data[y][x];
+
+        tile[4..6].copy_from_slice(&fg_color.get_code());
+        tile[10] = glyph;
+    }
+
+    // The bytes of whatever tile is at a position.
+    pub fn get(&self, x: usize, y: usize) -> [u8; 11] {
+        self.data[y][x]
+    }
+
+    pub fn add_layer(&mut self, layer: Layer) {
+        self.layers.push(layer);
+    }
+
+    // Draws every background layer on top of the initial screen, once everything has scrolled along `scrolled` columns.
+    pub fn draw_layers(&mut self, scrolled: usize) {
+        for layer in &self.layers {
+            let offset = (scrolled as f32 * layer.speed) as usize;
+
+            for (row, tiles) in layer.tiles.iter().enumerate() {
+                let y = layer.y + row;
+
+                if y >= self.height {
+                    break;
+                }
+
+                for x in 0..self.width {
+                    if let Some(tile) = tiles[(x + offset) % layer.width] {
+                        self.data[y][x] = tile;
+                    }
+                }
+            }
+        }
+    }
+
     // Sets actual bytes instead of using the abstracted tile.
     fn set_bytes(&mut self, x: usize, y: usize, tile: [u8; 11]) {
         self.data[y][x] = tile;
@@ -171,6 +276,21 @@ impl Screen {
         self.set_bytes(x, y, tile.calc());
     }
 
+    // Writes out some text starting at a position, one tile per character.
+    // Anything that goes past the edge of the screen is cut off.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, fg_color: FgColor, bg_color: BgColor) {
+        let mut tile = Tile::new(fg_color, bg_color, b' ').calc();
+
+        for (i, character) in text.bytes().enumerate() {
+            if x + i >= self.width {
+                break;
+            }
+
+            tile[10] = character;
+            self.set_bytes(x + i, y, tile);
+        }
+    }
+
     // Draws a horizontal line across the screen.
     pub fn line(&mut self, y: usize, tile: Tile) {
         self.data[y] = std::vec::from_elem(tile.calc(), self.width);
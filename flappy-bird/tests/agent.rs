@@ -0,0 +1,71 @@
+// Checks that the environment bots play in is repeatable, and that bots survive being saved and loaded.
+
+use flappy_bird::{
+    agent::{Action, INPUTS},
+    bot::Brain,
+    game::Game,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Plays a game with random flapping, giving back everything that was seen along the way.
+fn play(seed: u64) -> Vec<[f32; INPUTS]> {
+    let mut game = Game::with_size(80, 24, 0);
+    let mut actions = StdRng::seed_from_u64(seed);
+    let mut seen = vec![game.reset(seed).to_inputs()];
+
+    loop {
+        let action = if actions.gen_bool(0.2) {
+            Action::Flap
+        } else {
+            Action::Nothing
+        };
+
+        let (observation, _, done) = game.step(action);
+        seen.push(observation.to_inputs());
+
+        if done {
+            return seen;
+        }
+    }
+}
+
+#[test]
+fn same_seed_plays_the_same() {
+    for seed in 0..50 {
+        assert_eq!(play(seed), play(seed));
+    }
+}
+
+#[test]
+fn crashing_ends_the_game() {
+    let mut game = Game::with_size(80, 24, 0);
+    game.reset(0);
+
+    // Never flapping means hitting the ground.
+    let mut rewards = vec![];
+
+    loop {
+        let (_, reward, done) = game.step(Action::Nothing);
+        rewards.push(reward);
+
+        if done {
+            break;
+        }
+    }
+
+    assert!(*rewards.last().unwrap() < 0.0);
+    assert!(game.step(Action::Flap).2);
+}
+
+#[test]
+fn brains_save_and_load() {
+    let brain = Brain::random(&mut StdRng::seed_from_u64(0));
+    let path = std::env::temp_dir().join("flappy-bird-test-bot.txt");
+    let path = path.to_str().unwrap();
+
+    brain.save(path).unwrap();
+    let loaded = Brain::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(brain, loaded);
+}
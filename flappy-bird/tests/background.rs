@@ -0,0 +1,70 @@
+// Checks that the background layers repeat without any seams, and that the ones further back scroll by slower.
+
+use flappy_bird::{
+    background::{self, CLOUD_SPEED, GROUND_SPEED, SKYLINE_SPEED},
+    screen::{Layer, Screen},
+};
+
+const WIDTH: usize = 50;
+const HEIGHT: usize = 30;
+
+// A screen three times as wide as the layer was made for, with just that layer drawn on it.
+fn draw(layer: Layer, scrolled: usize) -> Screen {
+    let mut screen = Screen::with_size(WIDTH * 3, HEIGHT);
+
+    screen.add_layer(layer);
+    screen.draw_layers(scrolled);
+    screen
+}
+
+// Every layer, each one on its own.
+fn layers() -> Vec<Layer> {
+    background::layers(WIDTH, HEIGHT, 0)
+}
+
+#[test]
+fn layers_go_from_the_back_to_the_front() {
+    let speeds: Vec<f32> = layers().iter().map(|layer| layer.speed).collect();
+
+    assert_eq!(speeds, [CLOUD_SPEED, SKYLINE_SPEED, GROUND_SPEED]);
+    assert!(speeds.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn layers_repeat_without_seams() {
+    for scrolled in [0, 7, 123] {
+        for layer in layers() {
+            let width = layer.width;
+            let screen = draw(layer, scrolled);
+
+            for y in 0..HEIGHT {
+                for x in 0..screen.width - width {
+                    assert_eq!(
+                        screen.get(x, y),
+                        screen.get(x + width, y),
+                        "column {x} on row {y} doesn't line up after scrolling {scrolled}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn layers_scroll_at_their_own_speed() {
+    // After the pipes have moved 20 columns, the ground has too, but the skyline's only moved 6 and the clouds 2.
+    for (i, columns) in [2, 6, 20].into_iter().enumerate() {
+        let before = draw(layers().remove(i), 0);
+        let after = draw(layers().remove(i), 20);
+        let moved =
+            (0..HEIGHT).any(|y| (0..after.width).any(|x| after.get(x, y) != before.get(x, y)));
+
+        assert!(moved, "layer {i} didn't move");
+
+        for y in 0..HEIGHT {
+            for x in 0..after.width - columns {
+                assert_eq!(after.get(x, y), before.get(x + columns, y));
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Checks that the bird dies exactly when it's drawn over a pipe or the ground, by drawing random games and comparing.
+
+use flappy_bird::{
+    difficulty::MIN_PIPE_GAP,
+    game::{Game, PipeKind, Pipes, State},
+    play,
+    screen::{BgColor, FgColor, Screen, Tile},
+    PIPE_GAP,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const CASES: u32 = 10_000;
+
+const KINDS: [PipeKind; 3] = [PipeKind::Normal, PipeKind::Moving, PipeKind::Wide];
+
+// Makes a random game, with pipes that can be anywhere on the screen, including right at the edges.
+fn random_game(rng: &mut StdRng) -> (Game, Screen) {
+    let screen = Screen::with_size(rng.gen_range(10..120), rng.gen_range(12..50));
+    let mut game = Game::new(&screen, None);
+
+    for _ in 0..rng.gen_range(0..4) {
+        let kind = KINDS[rng.gen_range(0..KINDS.len())];
+        let gap = rng.gen_range(MIN_PIPE_GAP..=PIPE_GAP);
+        let mut pipe = Pipes::new(
+            kind,
+            rng.gen_range(0..=screen.width - kind.width()),
+            rng.gen_range(0..screen.height + gap),
+            gap,
+        );
+
+        // Moving pipes get moved some of the way along.
+        for _ in 0..rng.gen_range(0..20) {
+            pipe.tick();
+        }
+
+        game.pipes.push(pipe);
+    }
+
+    // Anywhere from just above the screen to the bottom of it.
+    game.player_y = rng.gen_range(-1.0..screen.height as f32);
+
+    (game, screen)
+}
+
+#[test]
+fn drawn_pipes_match_collisions() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let sky = Tile::new(FgColor::Black, BgColor::Blue, b'-').calc();
+
+    for _ in 0..CASES {
+        let (game, mut screen) = random_game(&mut rng);
+
+        play::draw_pipes(&mut screen, &game);
+
+        let rect = game.player_rect();
+        let drawn_over = game.player_y < 0.0
+            || (rect.from_x..rect.to_x).any(|x| screen.get(x, rect.from_y) != sky);
+
+        assert_eq!(
+            game.collides(),
+            drawn_over,
+            "bird at {} with pipes at {:?}",
+            game.player_y,
+            game.pipes
+                .iter()
+                .map(|pipe| (pipe.pos_x, pipe.offset_y))
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn bird_is_always_drawn_on_screen() {
+    let mut rng = StdRng::seed_from_u64(1);
+
+    for _ in 0..CASES {
+        let (mut game, mut screen) = random_game(&mut rng);
+
+        game.state = State::Playing;
+
+        // Long enough for the bird to crash and land on the ground, whatever it does.
+        for _ in 0..30 {
+            if rng.gen_bool(0.2) {
+                game.flap();
+            }
+
+            game.tick();
+            play::draw(&mut screen, &game);
+        }
+    }
+}
+
+#[test]
+fn ticks_without_pipes() {
+    let screen = Screen::with_size(40, 20);
+    let mut game = Game::new(&screen, None);
+
+    game.flap();
+    game.tick();
+
+    assert_eq!(game.state, State::Playing);
+    assert_eq!(game.pipes.len(), 1);
+}
@@ -0,0 +1,92 @@
+// Checks that however hard the game gets, every new pipe's gap is somewhere the bird can get to from the last one.
+
+use flappy_bird::{
+    difficulty::Difficulty,
+    game::{Game, Pipes, State},
+    screen::Screen,
+    GROUND_HEIGHT, PLAYER_LENGTH,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const GAMES: u32 = 200;
+const TICKS: u32 = 500;
+
+fn check_reachable(last: &Pipes, pipe: &Pipes, difficulty: &Difficulty) {
+    let columns = pipe
+        .pos_x
+        .saturating_sub(last.pos_x + last.width() + PLAYER_LENGTH);
+
+    // The furthest apart the middles of the two gaps can be, if both pipes have moved as far away from each other as they go.
+    let apart =
+        last.center_y.abs_diff(pipe.center_y) + last.kind.amplitude() + pipe.kind.amplitude();
+
+    // How far the bird has to go from the closest row of the last gap to the closest row of the new one.
+    let distance = apart.saturating_sub(last.gap + pipe.gap - 1);
+
+    assert!(
+        distance <= difficulty.reach(columns),
+        "can't get from {:?} to {:?} over {columns} columns at {difficulty:?}",
+        (last.kind, last.center_y, last.gap),
+        (pipe.kind, pipe.center_y, pipe.gap),
+    );
+}
+
+#[test]
+fn every_gap_is_reachable() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..GAMES {
+        let screen = Screen::with_size(rng.gen_range(40..200), rng.gen_range(20..60));
+        let mut game = Game::new(&screen, None);
+
+        for _ in 0..TICKS {
+            // The score keeps going up, but the bird is kept out of harm's way so the game never ends.
+            game.score += rng.gen_range(0..2);
+            game.player_y = 1.0;
+            game.velocity = 0.0;
+            game.state = State::Playing;
+
+            let difficulty = game.difficulty();
+            let before = game.pipes.len();
+
+            game.tick();
+
+            // A pipe just got added, as long as the old ones are still there to compare it with.
+            if game.pipes.len() > before && before > 0 {
+                let last = &game.pipes[game.pipes.len() - 2];
+                let pipe = &game.pipes[game.pipes.len() - 1];
+
+                check_reachable(last, pipe, &difficulty);
+            }
+        }
+    }
+}
+
+#[test]
+fn gaps_stay_on_screen() {
+    let mut rng = StdRng::seed_from_u64(1);
+
+    for _ in 0..GAMES {
+        let screen = Screen::with_size(rng.gen_range(40..200), rng.gen_range(20..60));
+        let mut game = Game::new(&screen, None);
+
+        for _ in 0..TICKS {
+            game.score += rng.gen_range(0..2);
+            game.player_y = 1.0;
+            game.velocity = 0.0;
+            game.state = State::Playing;
+            game.tick();
+
+            for pipe in &game.pipes {
+                let [top, bottom] = pipe.rects(game.height);
+
+                assert!(top.to_y >= 1, "no pipe above the gap");
+                assert!(
+                    bottom.from_y < game.height - GROUND_HEIGHT,
+                    "no pipe below the gap"
+                );
+                assert!(top.to_x <= game.width);
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Checks that racing the best run plays the same pipes again, with the ghost right where the bird was.
+
+use flappy_bird::{
+    game::{Game, State},
+    screen::Screen,
+};
+
+// Flaps whenever the bird drops below the middle, until the game's over, giving back where the bird was after every tick.
+fn play(game: &mut Game) -> Vec<f32> {
+    let mut path = vec![];
+
+    game.flap();
+
+    while game.state != State::GameOver {
+        if game.player_y > 12.0 {
+            game.flap();
+        }
+
+        game.tick();
+        path.push(game.player_y);
+
+        // The ghost keeps up with the bird when it's flown the same way.
+        if let Some(y) = game.ghost_y() {
+            assert_eq!(y, game.player_y);
+        }
+    }
+
+    path
+}
+
+#[test]
+fn racing_replays_the_best_run() {
+    let mut game = Game::with_size(80, 24, 7);
+    let path = play(&mut game);
+
+    let ghost = game.ghost.clone().unwrap();
+    assert_eq!(ghost.seed, 7);
+    assert_eq!(ghost.path, path);
+
+    game.race();
+    assert!(game.racing());
+    assert_eq!(play(&mut game), path);
+
+    // A run that only ties the best one doesn't replace it.
+    assert_eq!(game.ghost, Some(ghost));
+}
+
+#[test]
+fn retrying_plays_new_pipes() {
+    let mut game = Game::with_size(80, 24, 7);
+    play(&mut game);
+
+    game.retry();
+
+    assert!(game.ghost.is_some());
+    assert!(!game.racing());
+    assert_eq!(game.ghost_y(), None);
+}
+
+#[test]
+fn daily_retries_play_the_same_pipes() {
+    let screen = Screen::with_size(80, 24);
+    let mut game = Game::new(&screen, Some(19000));
+    let path = play(&mut game);
+
+    game.retry();
+
+    assert_eq!(game.seed, 19000);
+    assert!(game.racing());
+    assert_eq!(play(&mut game), path);
+}
@@ -0,0 +1,69 @@
+// Checks that the bird falls faster and faster up to a limit, and that flapping always sends it back up the same.
+
+use flappy_bird::{
+    game::{Game, State},
+    FLAP_VELOCITY, GRAVITY, PLAYER_SPAWN_Y, TERMINAL_VELOCITY,
+};
+
+// Tall enough that the bird can fall for a while, and wide enough that no pipes get to it.
+const WIDTH: usize = 200;
+const HEIGHT: usize = 100;
+
+fn playing() -> Game {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    game.state = State::Playing;
+    game
+}
+
+#[test]
+fn gravity_adds_up() {
+    let mut game = playing();
+    let mut expected = PLAYER_SPAWN_Y;
+
+    for tick in 1..=3 {
+        game.tick();
+        expected += GRAVITY * tick as f32;
+
+        assert!((game.velocity - GRAVITY * tick as f32).abs() < 0.001);
+        assert!((game.player_y - expected).abs() < 0.001);
+    }
+}
+
+#[test]
+fn falling_tops_out() {
+    let mut game = playing();
+
+    for _ in 0..30 {
+        let y = game.player_y;
+
+        game.tick();
+
+        assert!(game.velocity <= TERMINAL_VELOCITY);
+        assert!(game.player_y - y <= TERMINAL_VELOCITY);
+    }
+
+    assert_eq!(game.velocity, TERMINAL_VELOCITY);
+    assert_eq!(game.state, State::Playing);
+}
+
+#[test]
+fn flapping_goes_up_however_fast_the_bird_was_falling() {
+    let mut game = playing();
+
+    for falling in [0, 2, 20] {
+        for _ in 0..falling {
+            game.tick();
+        }
+
+        game.flap();
+
+        assert_eq!(game.velocity, FLAP_VELOCITY);
+
+        let y = game.player_y;
+
+        game.tick();
+
+        assert!(game.player_y < y);
+    }
+}
@@ -0,0 +1,125 @@
+// Checks that a game goes from waiting to start, to playing, to falling to the ground, to game over and back again.
+
+use flappy_bird::{
+    game::{Game, Medal, State},
+    BRONZE_SCORE, FLAP_VELOCITY, GOLD_SCORE, GROUND_HEIGHT, PLATINUM_SCORE, PLAYER_SPAWN_Y,
+    RETRY_DELAY, SILVER_SCORE,
+};
+
+const WIDTH: usize = 200;
+const HEIGHT: usize = 30;
+
+// A game where the bird is just about to hit the ground, having scored `score` points.
+fn about_to_crash(score: u32) -> Game {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    game.state = State::Playing;
+    game.score = score;
+    game.player_y = (HEIGHT - GROUND_HEIGHT) as f32 - 0.1;
+    game
+}
+
+// Ticks until the bird lands, giving back how many ticks that took.
+fn land(game: &mut Game) -> u32 {
+    let mut ticks = 0;
+
+    while game.state == State::Dying {
+        game.tick();
+        ticks += 1;
+
+        assert!(ticks < 100, "the bird never landed");
+    }
+
+    ticks
+}
+
+#[test]
+fn waits_for_the_first_flap() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    // The bird only bobs up and down a little, and no pipes come along.
+    for _ in 0..30 {
+        game.tick();
+
+        assert_eq!(game.state, State::Ready);
+        assert_eq!(game.velocity, 0.0);
+        assert!((game.player_y - PLAYER_SPAWN_Y).abs() <= 0.5);
+        assert!(game.pipes.is_empty());
+    }
+
+    game.flap();
+
+    assert_eq!(game.state, State::Playing);
+    assert_eq!(game.velocity, FLAP_VELOCITY);
+}
+
+#[test]
+fn crashing_falls_to_the_ground() {
+    // Crashing into a pipe high up means there's a long way to fall.
+    let mut game = about_to_crash(3);
+
+    game.player_y = -0.5;
+    game.tick();
+
+    assert_eq!(game.state, State::Dying);
+
+    // Flapping doesn't do anything on the way down.
+    game.flap();
+
+    assert!(land(&mut game) > 1);
+    assert_eq!(game.state, State::GameOver);
+    assert_eq!(game.player_row(), HEIGHT - GROUND_HEIGHT - 1);
+    assert_eq!(game.best, 3);
+}
+
+#[test]
+fn retrying_waits_a_moment_and_keeps_the_best_score() {
+    let mut game = about_to_crash(12);
+
+    game.tick();
+    land(&mut game);
+
+    assert_eq!(game.state, State::GameOver);
+
+    // Flapping away while the bird crashes doesn't skip straight past the score.
+    for _ in 0..RETRY_DELAY {
+        game.flap();
+
+        assert_eq!(game.state, State::GameOver);
+
+        game.tick();
+    }
+
+    game.flap();
+
+    assert_eq!(game.state, State::Ready);
+    assert_eq!(game.score, 0);
+    assert_eq!(game.best, 12);
+    assert!(game.pipes.is_empty());
+
+    // A worse game doesn't lower it.
+    game.state = State::Playing;
+    game.score = 4;
+    game.player_y = (HEIGHT - GROUND_HEIGHT) as f32 - 0.1;
+    game.tick();
+    land(&mut game);
+
+    assert_eq!(game.state, State::GameOver);
+    assert_eq!(game.best, 12);
+}
+
+#[test]
+fn medals_go_by_the_score() {
+    for (score, medal) in [
+        (0, None),
+        (BRONZE_SCORE - 1, None),
+        (BRONZE_SCORE, Some(Medal::Bronze)),
+        (SILVER_SCORE - 1, Some(Medal::Bronze)),
+        (SILVER_SCORE, Some(Medal::Silver)),
+        (GOLD_SCORE, Some(Medal::Gold)),
+        (PLATINUM_SCORE, Some(Medal::Platinum)),
+        (PLATINUM_SCORE * 10, Some(Medal::Platinum)),
+    ] {
+        assert_eq!(Medal::new(score), medal, "{score} points");
+    }
+}
@@ -0,0 +1,49 @@
+// How the game speeds up as the score goes up, a level at a time.
+
+// How many points it takes to go up a level, and the highest level there is.
+pub const LEVEL_SCORE: u32 = 10;
+pub const MAX_LEVEL: u32 = 10;
+
+// How many ticks go by between food spawning on the first level, how many fewer there are every level after it,
+// and the fewest there can be. That's every 2 seconds at the start, and every half a second at the most.
+pub const TICKS_FOR_FOOD: u8 = 40;
+pub const TICKS_FOR_FOOD_SHRINK: u8 = 4;
+pub const MIN_TICKS_FOR_FOOD: u8 = 10;
+
+// How many rows food falls every tick on the first level, and how much faster it gets every level after it.
+// Every food gets a bit extra on top of that, up to `FALL_SPEED_SPREAD`, so they don't all fall together.
+// Nothing ever falls more than a row a tick, or it could skip right past the stickman.
+pub const FALL_SPEED: f32 = 0.25;
+pub const FALL_SPEED_UP: f32 = 0.04;
+pub const FALL_SPEED_SPREAD: f32 = 0.1;
+pub const MAX_FALL_SPEED: f32 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Difficulty {
+    // Starts at 1.
+    pub level: u32,
+
+    // How many ticks go by between food spawning.
+    pub ticks_for_food: u8,
+
+    // The slowest and fastest new food can fall, in rows per tick.
+    pub min_speed: f32,
+    pub max_speed: f32,
+}
+
+impl Difficulty {
+    pub fn new(score: u32) -> Difficulty {
+        let level = (score / LEVEL_SCORE + 1).min(MAX_LEVEL);
+        let steps = level - 1;
+        let min_speed = (FALL_SPEED + FALL_SPEED_UP * steps as f32).min(MAX_FALL_SPEED);
+
+        Difficulty {
+            level,
+            ticks_for_food: TICKS_FOR_FOOD
+                .saturating_sub(TICKS_FOR_FOOD_SHRINK * steps as u8)
+                .max(MIN_TICKS_FOR_FOOD),
+            min_speed,
+            max_speed: (min_speed + FALL_SPEED_SPREAD).min(MAX_FALL_SPEED),
+        }
+    }
+}
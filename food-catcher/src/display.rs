@@ -1,82 +1,295 @@
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    slice,
+};
 
-use crate::game;
+use crate::game::{self, ModifierKind};
 use console::Term;
 
+#[derive(Clone, Copy)]
+pub enum FgColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Default,
+}
+
+#[derive(Clone, Copy)]
+pub enum BgColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Default,
+}
+
+impl FgColor {
+    pub fn get_code(&self) -> [u8; 2] {
+        match self {
+            FgColor::Black => *b"30",
+            FgColor::Red => *b"31",
+            FgColor::Green => *b"32",
+            FgColor::Yellow => *b"33",
+            FgColor::Blue => *b"34",
+            FgColor::Magenta => *b"35",
+            FgColor::Cyan => *b"36",
+            FgColor::White => *b"37",
+            FgColor::Default => *b"39",
+        }
+    }
+}
+
+impl BgColor {
+    pub fn get_code(&self) -> [u8; 2] {
+        match self {
+            BgColor::Black => *b"40",
+            BgColor::Red => *b"41",
+            BgColor::Green => *b"42",
+            BgColor::Yellow => *b"43",
+            BgColor::Blue => *b"44",
+            BgColor::Magenta => *b"45",
+            BgColor::Cyan => *b"46",
+            BgColor::White => *b"47",
+            BgColor::Default => *b"49",
+        }
+    }
+}
+
+// Glyphs that take up more than one byte, which get stored in a tile as one of these codes instead.
+// Any other byte is printed out as it is.
+pub const HEART: u8 = 0x80;
+
+// The bytes that actually get printed for a tile's glyph.
+fn glyph(code: &u8) -> &[u8] {
+    match *code {
+        HEART => "♥".as_bytes(),
+        _ => slice::from_ref(code),
+    }
+}
+
+pub struct Tile {
+    pub fg_color: FgColor,
+    pub bg_color: BgColor,
+    pub tile: u8,
+}
+
+impl Tile {
+    pub fn new(fg_color: FgColor, bg_color: BgColor, tile: u8) -> Tile {
+        Tile {
+            fg_color,
+            bg_color,
+            tile,
+        }
+    }
+
+    pub fn calc(&self) -> [u8; 11] {
+        let fg_code = self.fg_color.get_code();
+        let bg_code = self.bg_color.get_code();
+
+        [
+            b'\x1b', b'[', b'0', b';', fg_code[0], fg_code[1], b';', bg_code[0], bg_code[1], b'm',
+            self.tile,
+        ]
+    }
+}
+
+const STICKMAN: [[u8; 5]; 4] = [*b"(uwu)", *b" /|\\ ", *b"  |  ", *b" / \\ "];
+
 pub struct Screen {
-    data: Vec<Vec<char>>,
     pub height: usize,
     pub width: usize,
+
+    // Everything that's been drawn for the current frame, on top of the background.
+    data: Vec<Vec<[u8; 11]>>,
+
+    // The background, which never changes. Every frame starts out as a copy of it, see reset().
+    initial: Vec<Vec<[u8; 11]>>,
+
+    stdout: io::Stdout,
 }
 
 impl Screen {
     pub fn new(term: &Term) -> Screen {
-        let (mut height, width) = term.size();
-        height -= 1;
-    
-        let height: usize = height.into();
-        let width: usize = width.into();
-    
-        let mut data: Vec<Vec<char>> = std::vec::from_elem(
-            std::vec::from_elem(
-                ' ', width
-            ),
-            height
-        );
+        let (height, width) = term.size();
+
+        Screen::with_size(width.into(), (height - 1).into())
+    }
 
-        data[height-2] = std::vec::from_elem(
-            '-', width
+    // Makes a screen that isn't tied to the size of a terminal.
+    pub fn with_size(width: usize, height: usize) -> Screen {
+        let mut screen = Screen {
+            data: Vec::new(),
+            initial: Vec::new(),
+            height,
+            width,
+            stdout: io::stdout(),
+        };
+
+        screen.initial_draw();
+        screen.initial = screen.data.clone();
+
+        screen
+    }
+
+    // Draws the empty sky and the ground the stickman stands on.
+    fn initial_draw(&mut self) {
+        self.bg_color(Tile::new(FgColor::Default, BgColor::Default, b' '));
+        self.line(
+            self.height - 2,
+            Tile::new(FgColor::Green, BgColor::Default, b'-'),
         );
-        
-        Screen { data, height, width }
     }
 
-    pub fn render_stickman(&mut self, game: &game::Game, clear: bool) {
-        let player_y = self.height - 6;
+    // Wipes anything drawn since the last frame, leaving just the background.
+    pub fn reset(&mut self) {
+        self.data.clone_from(&self.initial);
+    }
 
-        const STICKMAN: [[char; 5]; 4] = [
-            ['(', 'u', 'w', 'u', ')'],
-            [' ', '/', '|', '\\', ' '],
-            [' ', ' ', '|', ' ', ' '],
-            [' ', '/', ' ', '\\', ' '],
-        ];
+    // The bytes of whatever tile is at a position.
+    pub fn get(&self, x: usize, y: usize) -> [u8; 11] {
+        self.data[y][x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, tile: Tile) {
+        if y < self.height && x < self.width {
+            self.data[y][x] = tile.calc();
+        }
+    }
+
+    // Writes out some text starting at a position, one tile per character.
+    // Anything that goes past the edge of the screen is cut off.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, fg_color: FgColor, bg_color: BgColor) {
+        for (i, character) in text.bytes().enumerate() {
+            self.set(x + i, y, Tile::new(fg_color, bg_color, character));
+        }
+    }
+
+    // Draws a horizontal line across the screen.
+    pub fn line(&mut self, y: usize, tile: Tile) {
+        self.data[y] = vec![tile.calc(); self.width];
+    }
+
+    // Wipes the screen and replaces it with one solid tile.
+    pub fn bg_color(&mut self, tile: Tile) {
+        self.data = vec![vec![tile.calc(); self.width]; self.height];
+    }
+
+    // The stickman turns blue while the magnet's on, so it's obvious that it can reach further.
+    pub fn draw_stickman(&mut self, game: &game::Game) {
+        let player_y = self.height - 6;
+        let color = if game.has_modifier(ModifierKind::Magnet) {
+            FgColor::Blue
+        } else {
+            FgColor::White
+        };
 
         for (i, row) in STICKMAN.iter().enumerate() {
-            for (j, char) in row.iter().enumerate() {
-                if clear {
-                    self.data[player_y+i][game.player_x+j] = ' '
-                } else {
-                    self.data[player_y+i][game.player_x+j] = *char;
+            for (j, character) in row.iter().enumerate() {
+                // The spaces around the stickman let the background show through.
+                if *character != b' ' {
+                    self.set(
+                        game.player_x + j,
+                        player_y + i,
+                        Tile::new(color, BgColor::Default, *character),
+                    );
                 }
             }
         }
     }
 
-    pub fn render_food(&mut self, game: &game::Game, clear: bool) {
+    pub fn draw_food(&mut self, game: &game::Game) {
         for food in &game.foods {
-            if clear {
-                self.data[food.pos_y][food.pos_x] = ' '
-            } else {
-                self.data[food.pos_y][food.pos_x] = '@'
-            }
+            self.set(
+                food.pos_x,
+                food.row(),
+                Tile::new(food.kind.color(), BgColor::Default, food.kind.glyph()),
+            );
+        }
+    }
+
+    // Writes a few lines of text in the middle of an empty screen, with a status line at the bottom.
+    pub fn render_message(&mut self, lines: &[&str], status: &str) {
+        self.reset();
+
+        let top = self.height.saturating_sub(lines.len()) / 2;
+
+        for (i, line) in lines.iter().enumerate() {
+            let left = self.width.saturating_sub(line.len()) / 2;
+
+            self.text(left, top + i, line, FgColor::Default, BgColor::Default);
         }
+
+        self.output(status);
     }
 
+    // Draws the food on top of the background, and the stickman on top of that.
     pub fn render(&mut self, game: &game::Game) {
-        let mut output: String = String::from("\x1b[H\r\x1b[2J\r");
-        
-        self.render_stickman(game, false);
-        self.render_food(game, false);
-        
-        for line in &self.data {
-            output.push_str(&line.iter().collect::<String>());
-        };
-        
-        self.render_stickman(game, true);
-        self.render_food(game, true);
-        
-        output.push_str(&format!("Score: {}  Lives: {}", game.score, game.lives));
-        
-        io::stdout().write_all(output.as_bytes()).unwrap();
-    }
-}
\ No newline at end of file
+        self.reset();
+        self.draw_food(game);
+        self.draw_stickman(game);
+
+        let mut status = format!(
+            "Level: {}  Score: {}  Lives: {}",
+            game.difficulty().level,
+            game.score,
+            game.lives
+        );
+
+        for modifier in &game.modifiers {
+            status.push_str(&format!(
+                "  {}: {}s",
+                modifier.kind.to_str(),
+                modifier.frames_left.div_ceil(100)
+            ));
+        }
+
+        self.output(&status);
+    }
+
+    // Writes the whole frame over the top of the last one.
+    fn output(&self, status: &str) {
+        let output = self.frame(status);
+        let mut lock = self.stdout.lock();
+
+        lock.write_all(&output).unwrap();
+        lock.flush().unwrap();
+    }
+
+    // Everything that gets written out to the terminal for the current frame.
+    // The terminal never gets cleared, since a cleared screen showing for even a moment is what makes it flicker.
+    pub fn frame(&self, status: &str) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut last_color: &[u8] = &[];
+
+        for (y, line) in self.data.iter().enumerate() {
+            // Every row starts with the cursor being put in place, so nothing depends on where the last row wrapped to.
+            output.extend_from_slice(format!("\x1b[{};1H", y + 1).as_bytes());
+
+            for tile in line {
+                // Colours stick around until they're changed, so they only need writing out when they do.
+                if tile[..10] != *last_color {
+                    output.extend_from_slice(&tile[..10]);
+                    last_color = &tile[..10];
+                }
+
+                output.extend_from_slice(glyph(&tile[10]));
+            }
+        }
+
+        // The status line gets cleared after it's written, so nothing from a longer one is left behind.
+        output.extend_from_slice(
+            format!("\x1b[{};1H\x1b[0m{status}\x1b[K", self.height + 1).as_bytes(),
+        );
+
+        output
+    }
+}
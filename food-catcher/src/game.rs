@@ -1,29 +1,150 @@
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::display;
+use crate::{
+    difficulty::Difficulty,
+    display::{self, FgColor},
+};
+
+// How many frames go by between ticks, with a frame being 10 ms.
+pub const TICK_FRAMES: u8 = 5;
+
+pub const STARTING_LIVES: u8 = 3;
+// Hearts can't give back more lives than this.
+pub const MAX_LIVES: u8 = 5;
+
+// How many points a high value item is worth.
+pub const HIGH_VALUE_POINTS: u32 = 5;
+
+// How many extra columns the stickman can catch things from on either side while the magnet's on.
+pub const MAGNET_REACH: usize = 3;
+
+// How long power-ups last for, in frames. That's 10 and 6 seconds.
+pub const MAGNET_FRAMES: u32 = 1000;
+pub const SLOW_MO_FRAMES: u32 = 600;
+
+// Every kind of thing that can fall, in the same order as their weights.
+pub const FOOD_KINDS: [FoodKind; 6] = [
+    FoodKind::Normal,
+    FoodKind::HighValue,
+    FoodKind::Bomb,
+    FoodKind::Heart,
+    FoodKind::Magnet,
+    FoodKind::SlowMo,
+];
+
+// How likely each kind is to spawn, compared to the others, unless it's set with `--weights`.
+pub const DEFAULT_SPAWN_WEIGHTS: [u32; 6] = [70, 8, 12, 3, 4, 3];
+
+// The biggest a spawn weight can be set to with `--weights`. Way more than enough to make one kind spawn nearly every time.
+pub const MAX_SPAWN_WEIGHT: u32 = 1_000_000;
 
 #[derive(PartialEq, Eq)]
 pub enum TickStatus {
     Ok,
     Exit,
-    NoTick
+    NoTick,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FoodKind {
+    // Worth one point.
+    Normal,
+    // Worth `HIGH_VALUE_POINTS`.
+    HighValue,
+    // Costs a life when it's caught, but nothing when it hits the ground.
+    Bomb,
+    // Gives back a life.
+    Heart,
+    // Turns on the magnet.
+    Magnet,
+    // Turns on slow motion.
+    SlowMo,
+}
+
+impl FoodKind {
+    pub fn glyph(&self) -> u8 {
+        match self {
+            FoodKind::Normal => b'@',
+            FoodKind::HighValue => b'$',
+            FoodKind::Bomb => b'*',
+            FoodKind::Heart => display::HEART,
+            FoodKind::Magnet => b'U',
+            FoodKind::SlowMo => b'~',
+        }
+    }
+
+    pub fn color(&self) -> FgColor {
+        match self {
+            FoodKind::Normal => FgColor::Green,
+            FoodKind::HighValue => FgColor::Yellow,
+            FoodKind::Bomb => FgColor::Red,
+            FoodKind::Heart => FgColor::Magenta,
+            FoodKind::Magnet => FgColor::Blue,
+            FoodKind::SlowMo => FgColor::Cyan,
+        }
+    }
+
+    // Whether letting it hit the ground costs a life. Only actual food does, everything else is optional.
+    pub fn must_catch(&self) -> bool {
+        matches!(self, FoodKind::Normal | FoodKind::HighValue)
+    }
 }
 
 pub struct Food {
-    pub pos_y: usize,
+    // How far down the food is, which is fractional so that it can fall slower than a row a tick.
+    pub pos_y: f32,
     pub pos_x: usize,
+    pub kind: FoodKind,
+
+    // How many rows the food falls every tick. Every food has its own, depending on the level it spawned on.
+    pub speed: f32,
 }
 
 impl Food {
-    pub fn new(game: &Game) -> Food {
-        let spawn_x = rand::thread_rng().gen_range(0..game.width);
+    pub fn new(game: &mut Game) -> Food {
+        let difficulty = game.difficulty();
+        let spawn_x = game.rng.gen_range(0..game.width);
+        let kind = game.random_kind();
+        let speed = game
+            .rng
+            .gen_range(difficulty.min_speed..=difficulty.max_speed);
+
         Food {
             pos_x: spawn_x,
-            pos_y: 0,
+            pos_y: 0.0,
+            kind,
+            speed,
+        }
+    }
+
+    // The row the food is drawn on.
+    pub fn row(&self) -> usize {
+        self.pos_y as usize
+    }
+}
+
+// The effects power-ups have, which wear off after a while.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModifierKind {
+    Magnet,
+    SlowMo,
+}
+
+impl ModifierKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ModifierKind::Magnet => "Magnet",
+            ModifierKind::SlowMo => "Slow-mo",
         }
     }
 }
 
+pub struct Modifier {
+    pub kind: ModifierKind,
+    // How many frames are left before it wears off.
+    pub frames_left: u32,
+}
+
 pub struct Game {
     pub player_x: usize,
     pub score: u32,
@@ -33,31 +154,106 @@ pub struct Game {
     pub width: usize,
     pub height: usize,
 
-    // The amount of ticks that need to go by for a food to be spawned.
-    pub ticks_for_food: u8,
+    // How likely each of `FOOD_KINDS` is to spawn.
+    pub spawn_weights: [u32; 6],
+
+    // Whichever power-ups are on right now.
+    pub modifiers: Vec<Modifier>,
 
     tick: u8,
     food_timer: u8,
+
+    // Where the food drops come from, so the same seed always drops them in the same places.
+    rng: StdRng,
 }
 
 impl Game {
-    pub fn new(screen: &display::Screen) -> Game {
+    pub fn new(screen: &display::Screen, seed: u64, spawn_weights: [u32; 6]) -> Game {
+        let mut game = Game::with_size(screen.width, screen.height, seed);
+
+        game.spawn_weights = spawn_weights;
+        game
+    }
+
+    // Makes a game that isn't tied to a screen, which is handy for playing without drawing anything.
+    // Everything spawns with the default weights.
+    pub fn with_size(width: usize, height: usize, seed: u64) -> Game {
         Game {
+            rng: StdRng::seed_from_u64(seed),
             player_x: 10,
             tick: 0,
             food_timer: 0,
-            ticks_for_food: 10,
-            width: screen.width,
-            height: screen.height,
+            spawn_weights: DEFAULT_SPAWN_WEIGHTS,
+            modifiers: vec![],
+            width,
+            height,
             score: 0,
-            lives: 3,
+            lives: STARTING_LIVES,
             foods: vec![],
         }
     }
 
+    // Picks what the next food is, going by the spawn weights.
+    fn random_kind(&mut self) -> FoodKind {
+        // Added up as a `u64`, so even the biggest weights can't overflow.
+        let total: u64 = self
+            .spawn_weights
+            .iter()
+            .map(|&weight| u64::from(weight))
+            .sum();
+
+        if total == 0 {
+            return FoodKind::Normal;
+        }
+
+        let mut roll = self.rng.gen_range(0..total);
+
+        for (kind, weight) in FOOD_KINDS.iter().zip(self.spawn_weights) {
+            let weight = u64::from(weight);
+
+            if roll < weight {
+                return *kind;
+            }
+
+            roll -= weight;
+        }
+
+        FoodKind::Normal
+    }
+
+    // How hard the game is right now, which only goes off the score.
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::new(self.score)
+    }
+
+    pub fn has_modifier(&self, kind: ModifierKind) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.kind == kind)
+    }
+
+    // Turns a power-up on, or starts its timer over if it's already on.
+    fn add_modifier(&mut self, kind: ModifierKind, frames: u32) {
+        self.modifiers.retain(|modifier| modifier.kind != kind);
+        self.modifiers.push(Modifier {
+            kind,
+            frames_left: frames,
+        });
+    }
+
+    fn catch(&mut self, kind: FoodKind) {
+        match kind {
+            FoodKind::Normal => self.score += 1,
+            FoodKind::HighValue => self.score += HIGH_VALUE_POINTS,
+            FoodKind::Bomb => self.lives = self.lives.saturating_sub(1),
+            FoodKind::Heart => self.lives = (self.lives + 1).min(MAX_LIVES),
+            FoodKind::Magnet => self.add_modifier(ModifierKind::Magnet, MAGNET_FRAMES),
+            FoodKind::SlowMo => self.add_modifier(ModifierKind::SlowMo, SLOW_MO_FRAMES),
+        }
+    }
+
     pub fn do_tick(&mut self) -> TickStatus {
         {
-            if self.food_timer == self.ticks_for_food {
+            // The time between food can shrink while it's counting down, so it only has to have been reached.
+            if self.food_timer >= self.difficulty().ticks_for_food {
                 self.food_timer = 0;
 
                 self.spawn_food()
@@ -66,46 +262,77 @@ impl Game {
             self.food_timer += 1;
         }
 
+        let reach = if self.has_modifier(ModifierKind::Magnet) {
+            MAGNET_REACH
+        } else {
+            0
+        };
+
+        // Whatever gets caught is dealt with afterwards, since catching things can change the whole game.
+        let mut caught = vec![];
+        let mut missed = 0;
+
         self.foods.retain(|food| {
-            let grounded = food.pos_y >= self.height - 3;
-            let touching_player = food.pos_x >= self.player_x
-                && food.pos_x <= self.player_x + 5
-                && food.pos_y >= self.height - 7;
+            let grounded = food.row() >= self.height - 3;
+            let touching_player = food.pos_x + reach >= self.player_x
+                && food.pos_x <= self.player_x + 5 + reach
+                && food.row() >= self.height - 7;
 
             if touching_player {
-                self.score += 1;
-            } else if grounded {
-                self.lives -= 1;
+                caught.push(food.kind);
+            } else if grounded && food.kind.must_catch() {
+                missed += 1;
             }
 
             !grounded && !touching_player
         });
 
+        for kind in caught {
+            self.catch(kind);
+        }
+
+        self.lives = self.lives.saturating_sub(missed);
+
         for food in &mut self.foods {
-            food.pos_y += 1;
+            food.pos_y += food.speed;
         }
 
-        if self.lives <= 0 {
-            return TickStatus::Exit
+        if self.lives == 0 {
+            return TickStatus::Exit;
         }
 
-        return TickStatus::Ok
+        TickStatus::Ok
     }
 
     pub fn spawn_food(&mut self) {
-        self.foods.push(Food::new(&self))
+        let food = Food::new(self);
+
+        self.foods.push(food)
     }
 
-    // Ticks every 20 frames, so 200 ms per tick.
+    // Ticks every 5 frames, so 50 ms per tick, or twice as slow in slow motion.
+    // Power-ups wear off by the frame, so slow motion doesn't make them last any longer.
     pub fn tick(&mut self) -> TickStatus {
-        if self.tick == 20 {
-            self.tick = 0;
-
-            return self.do_tick()
+        for modifier in &mut self.modifiers {
+            modifier.frames_left -= 1;
         }
 
+        self.modifiers.retain(|modifier| modifier.frames_left > 0);
+
+        let frames = if self.has_modifier(ModifierKind::SlowMo) {
+            TICK_FRAMES * 2
+        } else {
+            TICK_FRAMES
+        };
+
         self.tick += 1;
 
-        return TickStatus::NoTick
+        if self.tick >= frames {
+            self.tick = 0;
+
+            return self.do_tick();
+        }
+
+        TickStatus::NoTick
     }
 }
@@ -1,2 +1,6 @@
+pub mod difficulty;
 pub mod game;
-pub mod display;
\ No newline at end of file
+pub mod display;
+pub mod play;
+
+pub use play::run;
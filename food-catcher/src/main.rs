@@ -1,63 +1,57 @@
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::thread;
-use std::time;
+use std::{env, process::exit};
 
-use food_catcher::display;
 use food_catcher::game;
-use food_catcher::game::TickStatus;
-
-fn exit(exit_message: &str) {
-    println!("\r\x1b[2J\r\x1b[H\x1b[?25h{exit_message}");
-
-    std::process::exit(0)
-}
+use session::cleanup;
 
 fn main() {
+    let mut seed = None;
+    let mut spawn_weights = game::DEFAULT_SPAWN_WEIGHTS;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--daily" => seed = Some(daily::today_seed()),
+            "--weights" => match args.next().as_deref().map(weights) {
+                Some(Some(weights)) => spawn_weights = weights,
+                _ => usage("--weights needs 6 numbers from 0 to 1000000 that aren't all 0, like 70,8,12,3,4,3. They're for normal food, high value food, bombs, hearts, magnets and slow-mo, in that order."),
+            },
+            _ => usage(&format!("Unknown argument: {arg}")),
+        }
+    }
+
     println!("Loading...");
 
     let term = console::Term::stdout();
-    let mut screen = display::Screen::new(&term);
-    let game = Arc::new(Mutex::new(game::Game::new(&screen)));
-
-    term.hide_cursor().unwrap();
-
-    let game_mutex = Arc::clone(&game);
-
-    // Renders the game every 10 ms.
-    let render_thread = thread::spawn(move || loop {
-        thread::sleep(time::Duration::from_millis(10));
+    let result = food_catcher::run(&term, seed, spawn_weights);
 
-        let mut game = game_mutex.lock().unwrap();
-        screen.render(&game);
-
-        let status = game.tick();
-
-        if status == TickStatus::Exit {
-            exit("Game Over!")
-        }
-    });
-
-    let game_mutex = Arc::clone(&game);
-
-    // Gathers input in a seperate thread.
-    let input_thread: thread::JoinHandle<()> = thread::spawn(move || loop {
-        let key = term
-            .read_key()
-            .expect("an error occurred while reading input");
+    cleanup(&format!("Your best score was: {}", result.score));
+}
 
-        let mut game = game_mutex.lock().unwrap();
+// Prints what went wrong with the arguments, and how to use them.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!(
+        "Usage: food-catcher [--daily] [--weights <normal,high value,bomb,heart,magnet,slow-mo>]"
+    );
 
-        match key {
-            console::Key::ArrowLeft => game.player_x -= 1,
-            console::Key::ArrowRight => game.player_x += 1,
-            console::Key::Char('q') => {
-                exit("Quit.")
-            }
-            _ => (),
-        }
-    });
+    exit(1);
+}
 
-    render_thread.join().unwrap();
-    input_thread.join().unwrap();
+// Parses spawn weights like `70,8,12,3,4,3`, one for each of `game::FOOD_KINDS`.
+// At least one of them has to be more than 0, or nothing could ever spawn, and none of them can be over `game::MAX_SPAWN_WEIGHT`.
+fn weights(value: &str) -> Option<[u32; 6]> {
+    let weights: Vec<u32> = value
+        .split(',')
+        .map(|weight| {
+            weight
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&weight| weight <= game::MAX_SPAWN_WEIGHT)
+        })
+        .collect::<Option<_>>()?;
+    let weights: [u32; 6] = weights.try_into().ok()?;
+
+    weights.iter().any(|&weight| weight > 0).then_some(weights)
 }
@@ -0,0 +1,136 @@
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time;
+
+use console::Key;
+use console::Term;
+use session::ChannelMessage;
+use session::EndReason;
+use session::GameResult;
+
+use crate::display;
+use crate::game;
+use crate::game::TickStatus;
+
+// Keeps playing games until the player quits, and returns the result of the best one.
+// This is the entry point for anything that wants to host food catcher, like a launcher.
+// Every game drops the food in the same places if there's a `seed`, which is used for the daily challenge.
+// How often each kind of food spawns goes by `spawn_weights`, see `game::FOOD_KINDS` for which is which.
+pub fn run(term: &Term, seed: Option<u64>, spawn_weights: [u32; 6]) -> GameResult {
+    let (keys, input_thread) = session::spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    let mut screen = display::Screen::new(term);
+
+    let mut best: Option<GameResult> = None;
+
+    loop {
+        let (result, returned_screen) = play(screen, &keys, seed, spawn_weights);
+        screen = returned_screen;
+
+        best = match best {
+            Some(best) if best.score >= result.score => Some(best),
+            _ => Some(result),
+        };
+
+        if result.reason == EndReason::Quit {
+            break;
+        }
+
+        screen.render_message(
+            &[
+                "Game Over!",
+                "",
+                &format!("You had a score of: {}", result.score),
+            ],
+            "Press r to play again, or q to quit.",
+        );
+
+        if !session::play_again(&keys) {
+            break;
+        }
+    }
+
+    input_thread.join().unwrap();
+
+    // There's always at least one game played, so this is never empty.
+    best.unwrap()
+}
+
+// Plays a single game until it's either over or the player quits.
+// The screen is handed back afterwards so that it can be reused.
+fn play(
+    mut screen: display::Screen,
+    keys: &Receiver<Key>,
+    seed: Option<u64>,
+    spawn_weights: [u32; 6],
+) -> (GameResult, display::Screen) {
+    let seed = seed.unwrap_or_else(rand::random);
+    let game = Arc::new(Mutex::new(game::Game::new(&screen, seed, spawn_weights)));
+
+    let (sender, receiver) = mpsc::channel();
+    let game_mutex = Arc::clone(&game);
+
+    // Renders the game every 10 ms, until it gets told to stop.
+    let render_thread = thread::spawn(move || loop {
+        thread::sleep(time::Duration::from_millis(10));
+
+        let game = game_mutex.lock().unwrap();
+
+        // This is checked after locking the game, so a finished game never gets drawn.
+        if let Ok(msg) = receiver.try_recv() {
+            match msg {
+                ChannelMessage::Stop => return screen,
+            }
+        }
+
+        screen.render(&game);
+    });
+
+    // The game loop, it handles any input that came in and then ticks every 10 ms.
+    let reason = loop {
+        thread::sleep(time::Duration::from_millis(10));
+
+        let mut game = game.lock().unwrap();
+        let mut reason = None;
+
+        for key in keys.try_iter() {
+            match key {
+                Key::ArrowLeft => game.player_x = game.player_x.saturating_sub(1),
+                Key::ArrowRight => {
+                    // The stickman is 5 characters wide.
+                    if game.player_x + 5 < game.width {
+                        game.player_x += 1
+                    }
+                }
+                Key::Char('q') => reason = Some(EndReason::Quit),
+                _ => (),
+            }
+        }
+
+        if reason.is_none() && game.tick() == TickStatus::Exit {
+            reason = Some(EndReason::GameOver);
+        }
+
+        if let Some(reason) = reason {
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break reason;
+        }
+    };
+
+    let screen = render_thread.join().unwrap();
+    let game = game.lock().unwrap();
+
+    (
+        GameResult {
+            score: game.score,
+            reason,
+        },
+        screen,
+    )
+}
@@ -0,0 +1,123 @@
+// Checks that however high the score gets, the game stays within its limits and everything can still be caught.
+
+use food_catcher::{
+    difficulty::{
+        Difficulty, LEVEL_SCORE, MAX_FALL_SPEED, MAX_LEVEL, MIN_TICKS_FOR_FOOD, TICKS_FOR_FOOD,
+    },
+    game::{Food, FoodKind, Game},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Every score up to well past the last level, and then some silly ones.
+fn scores() -> impl Iterator<Item = u32> {
+    (0..LEVEL_SCORE * MAX_LEVEL * 3).chain([u32::MAX / 2, u32::MAX])
+}
+
+#[test]
+fn levels_stop_at_the_max() {
+    assert_eq!(Difficulty::new(0).level, 1);
+    assert_eq!(Difficulty::new(LEVEL_SCORE - 1).level, 1);
+    assert_eq!(Difficulty::new(LEVEL_SCORE).level, 2);
+    assert_eq!(
+        Difficulty::new(LEVEL_SCORE * (MAX_LEVEL - 1)).level,
+        MAX_LEVEL
+    );
+    assert_eq!(MAX_LEVEL, 10);
+
+    let mut last = 1;
+
+    for score in scores() {
+        let level = Difficulty::new(score).level;
+
+        assert!(
+            (last..=MAX_LEVEL).contains(&level),
+            "level {level} at {score}"
+        );
+        last = level;
+    }
+
+    assert_eq!(last, MAX_LEVEL);
+}
+
+#[test]
+fn food_keeps_spawning_at_a_sane_rate() {
+    assert_eq!((MIN_TICKS_FOR_FOOD, TICKS_FOR_FOOD), (10, 40));
+
+    let mut last = TICKS_FOR_FOOD;
+
+    for score in scores() {
+        let ticks = Difficulty::new(score).ticks_for_food;
+
+        assert!((10..=40).contains(&ticks), "{ticks} ticks at {score}");
+        assert!(ticks <= last, "food got slower at {score}");
+        last = ticks;
+    }
+}
+
+#[test]
+fn food_never_falls_too_fast_to_catch() {
+    // The stickman's catching rows are 4 tall, so anything falling a row a tick or less can't skip over them.
+    const { assert!(MAX_FALL_SPEED <= 1.0) };
+
+    for score in scores() {
+        let difficulty = Difficulty::new(score);
+
+        assert!(difficulty.min_speed > 0.0);
+        assert!(difficulty.min_speed <= difficulty.max_speed);
+        assert!(difficulty.max_speed <= MAX_FALL_SPEED, "{difficulty:?}");
+    }
+}
+
+#[test]
+fn spawned_food_falls_as_fast_as_the_level_says() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut game = Game::with_size(60, 30, 0);
+
+    for _ in 0..2000 {
+        game.score = rng.gen_range(0..LEVEL_SCORE * MAX_LEVEL * 2);
+        game.spawn_food();
+
+        let difficulty = game.difficulty();
+        let food = game.foods.pop().unwrap();
+
+        assert!(
+            (difficulty.min_speed..=difficulty.max_speed).contains(&food.speed),
+            "{} at {difficulty:?}",
+            food.speed
+        );
+    }
+}
+
+#[test]
+fn the_fastest_food_gets_caught() {
+    for height in 10..60 {
+        for score in [0, LEVEL_SCORE * 4, LEVEL_SCORE * MAX_LEVEL] {
+            let mut game = Game::with_size(60, height, 1);
+            game.score = score;
+
+            let speed = game.difficulty().max_speed;
+            let column = game.player_x + 2;
+
+            game.foods.push(Food {
+                pos_y: 0.0,
+                pos_x: column,
+                kind: FoodKind::Normal,
+                speed,
+            });
+
+            while !game.foods.is_empty() {
+                game.do_tick();
+
+                // Only the one food is being watched, anything else that spawns is taken away again.
+                game.foods
+                    .retain(|food| food.pos_x == column && food.speed == speed);
+            }
+
+            assert_eq!(
+                game.score,
+                score + 1,
+                "missed food falling at {speed} on a screen {height} tall"
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// Checks that food and the stickman get drawn in their own colours, and that frames get written over the last one
+// without ever clearing the terminal.
+
+use food_catcher::{
+    display::{BgColor, FgColor, Screen, Tile},
+    game::{Food, FoodKind, Game, Modifier, ModifierKind, FOOD_KINDS},
+};
+
+const WIDTH: usize = 40;
+const HEIGHT: usize = 20;
+
+fn food(kind: FoodKind, pos_x: usize, pos_y: f32) -> Food {
+    Food {
+        pos_y,
+        pos_x,
+        kind,
+        speed: 0.0,
+    }
+}
+
+// How many times `needle` shows up in `haystack`.
+fn count(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .filter(|window| *window == needle)
+        .count()
+}
+
+#[test]
+fn food_is_drawn_in_its_own_colour() {
+    let mut screen = Screen::with_size(WIDTH, HEIGHT);
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    game.foods = FOOD_KINDS
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| food(*kind, i * 2, 3.6))
+        .collect();
+
+    screen.draw_food(&game);
+
+    for (i, kind) in FOOD_KINDS.iter().enumerate() {
+        let expected = Tile::new(kind.color(), BgColor::Default, kind.glyph()).calc();
+
+        assert_eq!(screen.get(i * 2, 3), expected, "{kind:?}");
+    }
+}
+
+#[test]
+fn the_stickman_turns_blue_with_the_magnet() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+    let head = |game: &Game| {
+        let mut screen = Screen::with_size(WIDTH, HEIGHT);
+
+        screen.draw_stickman(game);
+        screen.get(game.player_x, HEIGHT - 6)
+    };
+
+    assert_eq!(
+        head(&game),
+        Tile::new(FgColor::White, BgColor::Default, b'(').calc()
+    );
+
+    game.modifiers.push(Modifier {
+        kind: ModifierKind::Magnet,
+        frames_left: 100,
+    });
+
+    assert_eq!(
+        head(&game),
+        Tile::new(FgColor::Blue, BgColor::Default, b'(').calc()
+    );
+}
+
+#[test]
+fn frames_never_clear_the_terminal() {
+    let mut screen = Screen::with_size(WIDTH, HEIGHT);
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    game.foods = vec![food(FoodKind::Normal, 5, 2.0)];
+    screen.draw_food(&game);
+    screen.draw_stickman(&game);
+
+    let frame = screen.frame("Score: 0");
+
+    assert_eq!(count(&frame, b"\x1b[2J"), 0);
+    assert_eq!(count(&frame, b"\x1b[J"), 0);
+
+    // Every row gets the cursor put in place first, then the status line goes under them.
+    for y in 1..=HEIGHT + 1 {
+        assert_eq!(
+            count(&frame, format!("\x1b[{y};1H").as_bytes()),
+            1,
+            "row {y}"
+        );
+    }
+
+    assert!(frame.ends_with(b"Score: 0\x1b[K"));
+}
+
+#[test]
+fn colours_are_only_written_when_they_change() {
+    let mut screen = Screen::with_size(WIDTH, HEIGHT);
+    let sky = Tile::new(FgColor::Default, BgColor::Default, b' ').calc();
+    let bomb = Tile::new(FgColor::Red, BgColor::Default, b'*').calc();
+
+    // Just the sky and the ground, so the colour changes to the ground and back again.
+    let frame = screen.frame("");
+
+    assert_eq!(count(&frame, &sky[..10]), 2);
+
+    // A bomb in the sky takes it over from the sky and hands it back.
+    screen.set(10, 4, Tile::new(FgColor::Red, BgColor::Default, b'*'));
+
+    let frame = screen.frame("");
+
+    assert_eq!(count(&frame, &bomb[..10]), 1);
+    assert_eq!(count(&frame, &sky[..10]), 3);
+}
+
+#[test]
+fn hearts_are_written_as_hearts() {
+    let mut screen = Screen::with_size(WIDTH, HEIGHT);
+    let mut game = Game::with_size(WIDTH, HEIGHT, 0);
+
+    game.foods = vec![food(FoodKind::Heart, 5, 2.0)];
+    screen.draw_food(&game);
+
+    let frame = screen.frame("");
+
+    assert_eq!(count(&frame, "♥".as_bytes()), 1);
+}
@@ -0,0 +1,134 @@
+// Checks what spawns goes by the spawn weights, and that power-ups do what they say for as long as they say.
+
+use food_catcher::game::{
+    Food, FoodKind, Game, ModifierKind, TickStatus, FOOD_KINDS, MAGNET_FRAMES, SLOW_MO_FRAMES,
+    TICK_FRAMES,
+};
+
+const WIDTH: usize = 60;
+const HEIGHT: usize = 30;
+
+// Spawns a lot of food, and gives back what kind each one was.
+fn spawn_kinds(weights: [u32; 6], count: usize) -> Vec<FoodKind> {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 1);
+    game.spawn_weights = weights;
+
+    (0..count)
+        .map(|_| {
+            game.spawn_food();
+            game.foods.pop().unwrap().kind
+        })
+        .collect()
+}
+
+// Drops something right on the stickman, and plays a tick so it gets caught.
+fn catch(game: &mut Game, kind: FoodKind) {
+    game.foods.push(Food {
+        pos_y: (HEIGHT - 5) as f32,
+        pos_x: game.player_x,
+        kind,
+        speed: 0.0,
+    });
+
+    game.do_tick();
+}
+
+// How many frames go by between each of the next few ticks.
+fn tick_gaps(game: &mut Game, count: usize) -> Vec<u32> {
+    let mut gaps = Vec::new();
+    let mut frames = 0;
+
+    while gaps.len() < count {
+        frames += 1;
+
+        if game.tick() != TickStatus::NoTick {
+            gaps.push(frames);
+            frames = 0;
+        }
+    }
+
+    gaps
+}
+
+#[test]
+fn kinds_with_no_weight_never_spawn() {
+    let kinds = spawn_kinds([0, 0, 0, 0, 1, 0], 500);
+
+    assert!(kinds.iter().all(|kind| *kind == FoodKind::Magnet));
+}
+
+#[test]
+fn kinds_spawn_as_often_as_their_weights_say() {
+    let kinds = spawn_kinds([1, 0, 3, 0, 0, 0], 4000);
+    let bombs = kinds.iter().filter(|kind| **kind == FoodKind::Bomb).count();
+
+    assert!(kinds
+        .iter()
+        .all(|kind| matches!(kind, FoodKind::Normal | FoodKind::Bomb)));
+    // That's 3000 expected, give or take.
+    assert!((2850..=3150).contains(&bombs), "{bombs} bombs");
+}
+
+#[test]
+fn huge_weights_dont_overflow() {
+    let kinds = spawn_kinds([u32::MAX; 6], 4000);
+
+    // Every kind is just as likely, so all of them should turn up.
+    for kind in FOOD_KINDS {
+        assert!(kinds.contains(&kind), "{kind:?} never spawned");
+    }
+}
+
+#[test]
+fn slow_mo_doubles_the_time_between_ticks() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 2);
+    // Nothing should run out of lives while this is going on.
+    game.lives = u8::MAX;
+
+    assert_eq!(tick_gaps(&mut game, 10), vec![TICK_FRAMES as u32; 10]);
+
+    catch(&mut game, FoodKind::SlowMo);
+
+    assert_eq!(tick_gaps(&mut game, 10), vec![TICK_FRAMES as u32 * 2; 10]);
+}
+
+#[test]
+fn power_ups_wear_off() {
+    for (kind, modifier, frames) in [
+        (FoodKind::Magnet, ModifierKind::Magnet, MAGNET_FRAMES),
+        (FoodKind::SlowMo, ModifierKind::SlowMo, SLOW_MO_FRAMES),
+    ] {
+        let mut game = Game::with_size(WIDTH, HEIGHT, 3);
+        game.lives = u8::MAX;
+
+        catch(&mut game, kind);
+
+        for _ in 0..frames - 1 {
+            game.tick();
+        }
+
+        assert!(game.has_modifier(modifier), "{modifier:?} wore off early");
+
+        game.tick();
+
+        assert!(!game.has_modifier(modifier), "{modifier:?} never wore off");
+        assert!(game.modifiers.is_empty());
+    }
+}
+
+#[test]
+fn catching_a_power_up_again_starts_it_over() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, 4);
+    game.lives = u8::MAX;
+
+    catch(&mut game, FoodKind::Magnet);
+
+    for _ in 0..100 {
+        game.tick();
+    }
+
+    catch(&mut game, FoodKind::Magnet);
+
+    assert_eq!(game.modifiers.len(), 1);
+    assert_eq!(game.modifiers[0].frames_left, MAGNET_FRAMES);
+}
@@ -0,0 +1,6 @@
+pub use daily::{date, seed, today};
+
+// The name a daily score is saved under, which is kept apart from the game's normal best score.
+pub fn score_key(game: &str, day: u64) -> String {
+    format!("{game}@{}", date(day))
+}
@@ -0,0 +1,3 @@
+pub mod daily;
+pub mod menu;
+pub mod scores;
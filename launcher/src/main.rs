@@ -0,0 +1,174 @@
+use std::io::{self, Write};
+
+use console::{Key, Term};
+use food_catcher::game::DEFAULT_SPAWN_WEIGHTS;
+use launcher::{
+    daily,
+    menu::{MenuData, MenuOption, StateMachine, DEFAULT_MENU},
+    scores::Scores,
+};
+use pong::rules::MatchRules;
+
+fn main() {
+    let term = Term::stdout();
+    let mut scores = Scores::load(&Scores::default_path());
+    let mut state = StateMachine::Menu(DEFAULT_MENU);
+    let mut status = String::from(
+        "Use the arrow keys to pick a game, enter to play it, and d for today's daily challenge.",
+    );
+
+    loop {
+        match &mut state {
+            StateMachine::Menu(menu) => {
+                term.hide_cursor().unwrap();
+                render_menu(menu, &scores, &status);
+
+                match term
+                    .read_key()
+                    .expect("an error occurred while reading input")
+                {
+                    Key::ArrowUp => menu.up(),
+                    Key::ArrowDown => menu.down(),
+                    Key::Enter => {
+                        state = match menu.selected() {
+                            MenuOption::Quit => StateMachine::Quit,
+                            game => StateMachine::Playing(game),
+                        }
+                    }
+                    Key::Char('d') => match menu.selected() {
+                        MenuOption::Quit => (),
+                        game => state = StateMachine::Daily(game),
+                    },
+                    Key::Char('q') => state = StateMachine::Quit,
+                    _ => (),
+                }
+            }
+            StateMachine::Playing(game) => {
+                let game = *game;
+
+                status = match launch(game, &term, None) {
+                    Ok(Some(score)) => submit(
+                        &mut scores,
+                        game.score_key().unwrap(),
+                        score,
+                        "best score",
+                        game,
+                    ),
+                    Ok(None) => no_score(game),
+                    Err(err) => err,
+                };
+
+                state = StateMachine::Menu(MenuData::at(game));
+            }
+            StateMachine::Daily(game) => {
+                let game = *game;
+                let day = daily::today();
+
+                status = match launch(game, &term, Some(daily::seed(day))) {
+                    Ok(Some(score)) => {
+                        let key = daily::score_key(game.score_key().unwrap(), day);
+
+                        submit(&mut scores, &key, score, "daily best", game)
+                    }
+                    Ok(None) => no_score(game),
+                    Err(err) => err,
+                };
+
+                state = StateMachine::Menu(MenuData::at(game));
+            }
+            StateMachine::Quit => break,
+        }
+    }
+
+    println!("\r\x1b[0m\x1b[2J\r\x1b[H\x1b[?25hBye!");
+}
+
+// Saves a score under `key` if it beats the last one, and says how it went.
+fn submit(scores: &mut Scores, key: &str, score: u64, kind: &str, game: MenuOption) -> String {
+    if !scores.submit(key, score) {
+        return format!("You scored {score} in {}.", game.to_str());
+    }
+
+    match scores.save() {
+        Ok(()) => format!("New {kind} of {score} in {}!", game.to_str()),
+        Err(err) => format!("New {kind} of {score}, but it couldn't be saved: {err}"),
+    }
+}
+
+// Says why nothing got saved after playing a game that didn't have a score worth keeping.
+fn no_score(game: MenuOption) -> String {
+    match game {
+        MenuOption::Pong => {
+            String::from("Pong only keeps a best score for matches against the CPU.")
+        }
+        game => format!("There's no score to keep from {}.", game.to_str()),
+    }
+}
+
+// Runs a game in the current terminal until the player quits it, and returns the best score they got.
+// There isn't always one, like after a two player game of pong, where it'd be nobody's personal best.
+// Every game is played from the `seed` if there is one, which is how daily challenges work.
+fn launch(game: MenuOption, term: &Term, seed: Option<u64>) -> Result<Option<u64>, String> {
+    match game {
+        MenuOption::Pong => Ok(
+            pong::run(term, MatchRules::default(), None, None, None, seed)
+                .best
+                .map(u64::from),
+        ),
+        MenuOption::FlappyBird => Ok(Some(flappy_bird::run(term, seed).score.into())),
+        MenuOption::FoodCatcher => Ok(Some(
+            food_catcher::run(term, seed, DEFAULT_SPAWN_WEIGHTS)
+                .score
+                .into(),
+        )),
+        MenuOption::SpaceInvaders => {
+            if !invaders::fits_terminal() {
+                return Err(format!(
+                    "Space Invaders needs a terminal of at least {}x{}.",
+                    invaders::MIN_WIDTH,
+                    invaders::MIN_HEIGHT
+                ));
+            }
+
+            Ok(Some(invaders::run(None, seed).score))
+        }
+        MenuOption::Quit => unreachable!("quitting doesn't launch a game"),
+    }
+}
+
+// Draws the menu, with every game's best score next to it, and the best from today's daily challenge.
+fn render_menu(menu: &MenuData, scores: &Scores, status: &str) {
+    let mut output = String::from("\x1b[0m\x1b[H\r\x1b[2J\r");
+    let today = daily::today();
+
+    output.push_str(&format!(
+        "\n  Rust Games\n\n  Daily challenge: {}\n\n",
+        daily::date(today)
+    ));
+
+    for (i, option) in menu.options.iter().enumerate() {
+        let cursor = if i == menu.cursor_index { '>' } else { ' ' };
+        let best = match option.score_key() {
+            Some(key) => {
+                let best = scores
+                    .get(key)
+                    .map_or(String::from("-"), |score| score.to_string());
+                let daily = scores
+                    .get(&daily::score_key(key, today))
+                    .map_or(String::from("-"), |score| score.to_string());
+
+                format!("Best: {best:<8}Today: {daily}")
+            }
+            None => String::new(),
+        };
+
+        output.push_str(&format!("  {cursor} {:<16}{best}\n", option.to_str()));
+    }
+
+    output.push_str(&format!("\n  {status}"));
+
+    let mut stdout = io::stdout().lock();
+
+    stdout.write_all(output.as_bytes()).unwrap();
+    stdout.flush().unwrap();
+}
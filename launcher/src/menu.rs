@@ -0,0 +1,85 @@
+pub const DEFAULT_MENU: MenuData = MenuData {
+    options: [
+        MenuOption::Pong,
+        MenuOption::FlappyBird,
+        MenuOption::FoodCatcher,
+        MenuOption::SpaceInvaders,
+        MenuOption::Quit,
+    ],
+    cursor_index: 0,
+};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MenuOption {
+    Pong,
+    FlappyBird,
+    FoodCatcher,
+    SpaceInvaders,
+    Quit,
+}
+
+impl MenuOption {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            MenuOption::Pong => "Pong",
+            MenuOption::FlappyBird => "Flappy Bird",
+            MenuOption::FoodCatcher => "Food Catcher",
+            MenuOption::SpaceInvaders => "Space Invaders",
+            MenuOption::Quit => "Quit",
+        }
+    }
+
+    // The name the game's best score is saved under, if it has one.
+    pub fn score_key(&self) -> Option<&'static str> {
+        match self {
+            MenuOption::Pong => Some("pong"),
+            MenuOption::FlappyBird => Some("flappy-bird"),
+            MenuOption::FoodCatcher => Some("food-catcher"),
+            MenuOption::SpaceInvaders => Some("space-invaders"),
+            MenuOption::Quit => None,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct MenuData {
+    pub options: [MenuOption; 5],
+    pub cursor_index: usize,
+}
+
+impl MenuData {
+    // A fresh menu with the cursor on a specific option, used when coming back from a game.
+    pub fn at(option: MenuOption) -> MenuData {
+        let mut menu = DEFAULT_MENU;
+
+        menu.cursor_index = menu.options.iter().position(|o| *o == option).unwrap_or(0);
+
+        menu
+    }
+
+    pub fn up(&mut self) {
+        if self.cursor_index > 0 {
+            self.cursor_index -= 1;
+        }
+    }
+
+    pub fn down(&mut self) {
+        if self.cursor_index < self.options.len() - 1 {
+            self.cursor_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> MenuOption {
+        self.options[self.cursor_index]
+    }
+}
+
+// The different states the launcher can be in.
+#[derive(PartialEq)]
+pub enum StateMachine {
+    Menu(MenuData),
+    Playing(MenuOption),
+    // Playing today's daily challenge, where everyone gets the same game.
+    Daily(MenuOption),
+    Quit,
+}
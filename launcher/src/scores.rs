@@ -0,0 +1,67 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// The best score for every game, kept in a small text file with one `game score` pair per line.
+pub struct Scores {
+    path: PathBuf,
+    best: BTreeMap<String, u64>,
+}
+
+impl Scores {
+    // Where the scores are saved by default, which is the home directory if there is one.
+    pub fn default_path() -> PathBuf {
+        env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".rust-games-scores")
+    }
+
+    // Loads the scores from a file. A missing or broken file just means there aren't any scores yet.
+    pub fn load(path: &Path) -> Scores {
+        let mut best = BTreeMap::new();
+
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines() {
+                if let Some((game, score)) = line.split_once(' ') {
+                    if let Ok(score) = score.trim().parse() {
+                        best.insert(game.to_string(), score);
+                    }
+                }
+            }
+        }
+
+        Scores {
+            path: path.to_path_buf(),
+            best,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let contents: String = self
+            .best
+            .iter()
+            .map(|(game, score)| format!("{game} {score}\n"))
+            .collect();
+
+        fs::write(&self.path, contents)
+    }
+
+    pub fn get(&self, game: &str) -> Option<u64> {
+        self.best.get(game).copied()
+    }
+
+    // Records a score, and returns whether it's a new best.
+    pub fn submit(&mut self, game: &str, score: u64) -> bool {
+        match self.best.get(game) {
+            Some(best) if *best >= score => false,
+            _ => {
+                self.best.insert(game.to_string(), score);
+
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    game::{paddle_scale, Game, Player},
+    paddle::MAX_SPEED,
+};
+
+// How good the CPU player is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    // How many ticks it takes for the CPU to react once the ball starts heading towards it.
+    pub fn reaction_delay(&self) -> u8 {
+        match self {
+            Difficulty::Easy => 8,
+            Difficulty::Medium => 4,
+            Difficulty::Hard => 1,
+        }
+    }
+
+    // The fastest the CPU lets its paddle go, in rows per tick.
+    pub fn max_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Medium => 1.2,
+            Difficulty::Hard => MAX_SPEED,
+        }
+    }
+
+    // How many rows off the CPU's guess of where the ball will end up can be.
+    fn prediction_error(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 4.0,
+            Difficulty::Medium => 2.0,
+            Difficulty::Hard => 0.5,
+        }
+    }
+}
+
+// Predicts when the first ball to reach a player's paddle gets there, and where along the paddle it'll be then,
+// including any bounces off the sides of the court.
+// Returns `None` if every ball is moving away from the paddle, or isn't moving towards it at all.
+pub fn predict(game: &Game, player: Player) -> Option<(f32, f32)> {
+    let face = game.paddle_face(player);
+
+    let (ticks, ball) = game
+        .balls
+        .iter()
+        .map(|ball| match player.horizontal() {
+            true => ((face - ball.y) / ball.velocity_y, ball),
+            false => ((face - ball.x) / ball.velocity_x, ball),
+        })
+        .filter(|(ticks, _)| ticks.is_finite() && *ticks >= 0.0)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))?;
+
+    let (along, velocity, size) = match player.horizontal() {
+        true => (ball.x, ball.velocity_x, game.width as f32 - 1.0),
+        false => (ball.y, ball.velocity_y, game.height as f32),
+    };
+    let position = along + velocity * ticks;
+
+    // Every bounce mirrors the ball's path, so the path repeats every two court sizes.
+    // Folding the unbounced position back into the court gives where it'll actually be.
+    let position = position.rem_euclid(size * 2.0);
+
+    if position > size {
+        Some((ticks, size * 2.0 - position))
+    } else {
+        Some((ticks, position))
+    }
+}
+
+// A computer player, which controls one of the paddles.
+pub struct Cpu {
+    pub difficulty: Difficulty,
+    pub player: Player,
+
+    // Where the CPU is moving its paddle towards.
+    target: f32,
+
+    // Counts down until the CPU reacts to the ball heading its way.
+    reaction_timer: u8,
+
+    // Whether a ball was heading towards the CPU last tick.
+    incoming: bool,
+
+    // How many ticks it was until the ball heading towards the CPU got there, as of last tick.
+    arrival: f32,
+}
+
+impl Cpu {
+    pub fn new(difficulty: Difficulty, player: Player, game: &Game) -> Cpu {
+        Cpu {
+            difficulty,
+            player,
+            target: game.paddle(player).position,
+            reaction_timer: 0,
+            incoming: false,
+            arrival: 0.0,
+        }
+    }
+
+    // Decides where to go, and moves its paddle towards it. Should be called once every game tick.
+    pub fn tick(&mut self, game: &mut Game) {
+        // A CPU that's been knocked out of a free-for-all has nothing left to do.
+        if !game.in_play(self.player) {
+            return;
+        }
+
+        // The CPU serves as soon as it's allowed to.
+        game.serve(self.player);
+
+        match predict(game, self.player) {
+            Some((arrival, predicted)) => {
+                // A ball that's further away than the last one was means that one's been dealt with,
+                // and there's a new ball to react to.
+                if !self.incoming || arrival > self.arrival + 1.0 {
+                    self.incoming = true;
+                    self.reaction_timer = self.difficulty.reaction_delay();
+                }
+
+                self.arrival = arrival;
+
+                if self.reaction_timer > 0 {
+                    self.reaction_timer -= 1;
+
+                    // The CPU only makes up its mind once it's done reacting, and then sticks with it.
+                    if self.reaction_timer == 0 {
+                        let error = self.difficulty.prediction_error();
+
+                        self.target = predicted + game.jitter(error);
+                    }
+                }
+            }
+            None => {
+                // Drift back towards the middle while the ball is heading away.
+                self.incoming = false;
+                self.target = match self.player.horizontal() {
+                    true => game.width as f32 / 2.0,
+                    false => game.height as f32 / 2.0,
+                };
+            }
+        }
+
+        let paddle = game.paddle(self.player);
+
+        // Paddle collisions are checked against the tile the ball rounds to.
+        let target = self.target.round();
+
+        // The top and bottom paddles move sideways, where tiles are smaller.
+        let scale = paddle_scale(self.player);
+
+        // The paddle keeps sliding for a bit after it's let go, so the CPU aims for where it'll stop rather than where it is.
+        // It only pushes if that means stopping closer to its target, without going over its max speed.
+        let resting = paddle.resting_position(scale);
+        let direction = (target - resting).signum() as i8;
+
+        let mut pushed = *paddle;
+        pushed.nudge(direction);
+
+        let mut next = pushed;
+        next.tick(f32::NEG_INFINITY, f32::INFINITY, scale);
+
+        if (pushed.resting_position(scale) - target).abs() < (resting - target).abs()
+            && next.velocity.abs() <= self.difficulty.max_speed() * scale
+        {
+            game.nudge_paddle(self.player, direction);
+        }
+    }
+}
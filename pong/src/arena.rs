@@ -0,0 +1,134 @@
+use std::{fs, io};
+
+use crate::game::{Game, Player};
+
+// A block the ball bounces off, in playfield tiles.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Obstacle {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Obstacle {
+    // Whether a point is on one of the obstacle's tiles.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let (x, y) = (x.round(), y.round());
+
+        x >= self.x as f32
+            && x < (self.x + self.width) as f32
+            && y >= self.y as f32
+            && y < (self.y + self.height) as f32
+    }
+}
+
+// A layout of obstacles, loaded from a text map where `#` is an obstacle and anything else is empty space.
+// Lines starting with `;` are comments.
+//
+// The map gets stretched over the court between the two paddles, so its size only decides how detailed the layout is.
+// For example, this puts a pillar in each corner of the court:
+//
+// #.....#
+// .......
+// .......
+// #.....#
+pub struct Arena {
+    rows: Vec<Vec<bool>>,
+}
+
+impl Arena {
+    pub fn load(path: &str) -> io::Result<Arena> {
+        Arena::parse(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn parse(map: &str) -> Result<Arena, String> {
+        let rows: Vec<Vec<bool>> = map
+            .lines()
+            .filter(|line| !line.starts_with(';'))
+            .map(|line| line.chars().map(|tile| tile == '#').collect())
+            .collect();
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        if columns == 0 {
+            return Err(String::from("the arena map is empty"));
+        }
+
+        // Short lines are just padded out with empty space.
+        let rows = rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(columns, false);
+                row
+            })
+            .collect();
+
+        Ok(Arena { rows })
+    }
+
+    // Works out where the obstacles go on a game's court, which has to be set up for a free-for-all first if it's going to be one.
+    // Anything that would cover the middle gets left out, since that's where chaos pong serves its extra balls from.
+    pub fn obstacles(&self, game: &Game) -> Vec<Obstacle> {
+        // A column of space is left next to each paddle, so the ball can always reach them.
+        let left = game.paddle_1_face() as usize + 2;
+        let right = game.paddle_2_face() as usize - 1;
+        let court_width = right.saturating_sub(left);
+
+        // The same goes for the top and bottom paddles, when there's someone sitting there in a free-for-all.
+        let top = match game.in_play(Player::Three) {
+            true => game.paddle_3_face() as usize + 2,
+            false => 0,
+        };
+        let bottom = match game.in_play(Player::Four) {
+            true => (game.paddle_4_face() as usize).saturating_sub(1),
+            false => game.height,
+        };
+        let court_height = bottom.saturating_sub(top);
+
+        let columns = self.rows[0].len();
+        let rows = self.rows.len();
+
+        let column_x = |column: usize| left + column * court_width / columns;
+        let row_y = |row: usize| top + row * court_height / rows;
+
+        let mut obstacles = Vec::new();
+
+        for (row, tiles) in self.rows.iter().enumerate() {
+            let mut column = 0;
+
+            // Every run of obstacles in a row turns into one long obstacle.
+            while column < columns {
+                if !tiles[column] {
+                    column += 1;
+                    continue;
+                }
+
+                let start = column;
+
+                while column < columns && tiles[column] {
+                    column += 1;
+                }
+
+                let obstacle = Obstacle {
+                    x: column_x(start),
+                    y: row_y(row),
+                    width: column_x(column) - column_x(start),
+                    height: row_y(row + 1) - row_y(row),
+                };
+
+                let middle = (game.width as f32 / 2.0, game.height as f32 / 2.0);
+
+                if obstacle.width > 0
+                    && obstacle.height > 0
+                    && !obstacle.contains(middle.0, middle.1)
+                {
+                    obstacles.push(obstacle);
+                }
+            }
+        }
+
+        obstacles
+    }
+}
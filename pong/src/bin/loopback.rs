@@ -0,0 +1,368 @@
+// A test harness for online play. It plays a match between two headless copies of itself over loopback,
+// with packets held back and dropped on purpose, then checks both of them ended up with the same game.
+// This goes over real sockets and real time, the same checks without either are in `tests/rollback.rs`.
+//
+// Usage: loopback [--frames <n>] [--latency <ms>] [--loss <percent>] [--delay <frames>] [--seed <n>]
+
+use std::{
+    collections::VecDeque,
+    env, io,
+    net::UdpSocket,
+    process::{exit, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use pong::{
+    game::Player,
+    net::{
+        self,
+        protocol::Message,
+        rollback::{Input, Session},
+    },
+    rules::MatchRules,
+    TICK_TIME,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// The size of the playfield both sides play on.
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// How long a peer keeps going after it's done, so the other side gets its last acknowledgements.
+const LINGER: Duration = Duration::from_secs(1);
+
+// How long a peer waits without hearing anything before it gives up.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy)]
+struct Settings {
+    frames: u32,
+    latency: u64,
+    loss: u32,
+    delay: u32,
+    seed: u64,
+}
+
+fn main() {
+    let mut settings = Settings {
+        frames: 300,
+        latency: 100,
+        loss: 10,
+        delay: net::DEFAULT_INPUT_DELAY,
+        seed: 1,
+    };
+
+    // Which side to play and the ports to use, when this is one of the two peers.
+    let mut peer = None;
+    let mut port = 0;
+    let mut remote = 0;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let value = args.next();
+
+        match arg.as_str() {
+            "--frames" => settings.frames = number(&arg, value),
+            "--latency" => settings.latency = number(&arg, value),
+            "--loss" => settings.loss = number::<u32>(&arg, value).min(100),
+            "--delay" => settings.delay = number(&arg, value),
+            "--seed" => settings.seed = number(&arg, value),
+            "--peer" => peer = Some(number::<u8>(&arg, value)),
+            "--port" => port = number(&arg, value),
+            "--remote" => remote = number(&arg, value),
+            _ => {
+                eprintln!("Unknown argument: {arg}");
+                eprintln!("Usage: loopback [--frames <n>] [--latency <ms>] [--loss <percent>] [--delay <frames>] [--seed <n>]");
+
+                exit(1);
+            }
+        }
+    }
+
+    match peer {
+        Some(side) => {
+            let player = if side == 1 { Player::One } else { Player::Two };
+
+            if let Err(err) = run_peer(settings, player, port, remote) {
+                eprintln!("Peer {side} failed: {err}");
+
+                exit(1);
+            }
+        }
+        None => run_harness(settings),
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(number)) => number,
+        _ => {
+            eprintln!("{flag} needs a number");
+
+            exit(1);
+        }
+    }
+}
+
+// Starts both peers as separate processes, and compares how their games ended up.
+fn run_harness(settings: Settings) {
+    // Letting the system pick two free ports, which get handed to the peers.
+    let ports = [free_port(), free_port()];
+    let exe = env::current_exe().expect("couldn't find the harness executable");
+
+    println!(
+        "Playing {} frames with {} ms of latency, {}% packet loss and an input delay of {} frames...",
+        settings.frames, settings.latency, settings.loss, settings.delay
+    );
+
+    let peers: Vec<_> = [(1, ports[0], ports[1]), (2, ports[1], ports[0])]
+        .into_iter()
+        .map(|(side, port, remote)| {
+            Command::new(&exe)
+                .args(["--peer", &side.to_string()])
+                .args(["--port", &port.to_string(), "--remote", &remote.to_string()])
+                .args(["--frames", &settings.frames.to_string()])
+                .args(["--latency", &settings.latency.to_string()])
+                .args(["--loss", &settings.loss.to_string()])
+                .args(["--delay", &settings.delay.to_string()])
+                .args(["--seed", &settings.seed.to_string()])
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("couldn't start a peer")
+        })
+        .collect();
+
+    let reports: Vec<String> = peers
+        .into_iter()
+        .map(|peer| {
+            let output = peer.wait_with_output().expect("a peer crashed");
+
+            if !output.status.success() {
+                exit(1);
+            }
+
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        })
+        .collect();
+
+    let mut parsed = Vec::new();
+
+    for (name, report) in ["Host", "Guest"].iter().zip(&reports) {
+        let report = match Report::parse(report) {
+            Ok(report) => report,
+            Err(err) => {
+                println!("{name} sent a broken report: {err}");
+
+                exit(1);
+            }
+        };
+
+        println!(
+            "{name}: checksum {}, {} rollbacks, {} stalls, desynced: {}",
+            report.checksum, report.rollbacks, report.stalls, report.desynced
+        );
+
+        parsed.push(report);
+    }
+
+    if parsed[0].checksum == parsed[1].checksum && parsed.iter().all(|report| !report.desynced) {
+        println!("Both games match.");
+    } else {
+        println!("The games don't match!");
+
+        exit(1);
+    }
+}
+
+// How a peer's game ended up, which it prints out as its final checksum, how many times it went back,
+// how often it waited, and whether it desynced.
+struct Report {
+    checksum: String,
+    rollbacks: u32,
+    stalls: u32,
+    desynced: bool,
+}
+
+impl Report {
+    fn parse(report: &str) -> Result<Report, String> {
+        let fields: Vec<&str> = report.split(' ').collect();
+        let field = |index: usize, name: &str| {
+            fields
+                .get(index)
+                .copied()
+                .ok_or_else(|| format!("the {name} is missing from `{report}`"))
+        };
+        let parse_field = |index: usize, name: &str| {
+            let value = field(index, name)?;
+
+            value
+                .parse()
+                .map_err(|_| format!("`{value}` isn't a valid {name}"))
+        };
+
+        Ok(Report {
+            checksum: field(0, "checksum")?.to_string(),
+            rollbacks: parse_field(1, "rollback count")?,
+            stalls: parse_field(2, "stall count")?,
+            desynced: field(3, "desync flag")? == "true",
+        })
+    }
+}
+
+fn free_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0")
+        .and_then(|socket| socket.local_addr())
+        .expect("couldn't find a free port")
+        .port()
+}
+
+// Sends packets like a bad network would, by holding each one back and losing some of them.
+struct Lossy {
+    socket: UdpSocket,
+    latency: Duration,
+    loss: u32,
+    rng: StdRng,
+    queue: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl Lossy {
+    fn send(&mut self, message: &Message) {
+        if self.rng.gen_range(0..100) < self.loss {
+            return;
+        }
+
+        self.queue
+            .push_back((Instant::now() + self.latency, message.encode()));
+    }
+
+    // Actually sends every packet that's been held back long enough.
+    fn flush(&mut self) {
+        while let Some((due, _)) = self.queue.front() {
+            if *due > Instant::now() {
+                break;
+            }
+
+            let (_, packet) = self.queue.pop_front().unwrap();
+            let _ = self.socket.send(&packet);
+        }
+    }
+}
+
+// Plays one side of the match with random inputs, then prints how it went.
+fn run_peer(settings: Settings, player: Player, port: u16, remote: u16) -> io::Result<()> {
+    let socket = UdpSocket::bind(("127.0.0.1", port))?;
+
+    socket.connect(("127.0.0.1", remote))?;
+    socket.set_nonblocking(true)?;
+
+    let side = player.index() as u64 + 1;
+
+    let rules = MatchRules {
+        // Long enough that the match doesn't end before the frames run out.
+        best_of: 99,
+        // Power-ups use the game's randomness too, so they're on to check that stays in sync.
+        power_ups: true,
+        ..MatchRules::default()
+    };
+
+    let game = net::new_game(WIDTH, HEIGHT, rules, settings.seed);
+    let mut session = Session::new(game, player, settings.delay);
+    let mut link = Lossy {
+        socket: socket.try_clone()?,
+        latency: Duration::from_millis(settings.latency),
+        loss: settings.loss,
+        rng: StdRng::seed_from_u64(settings.seed + side),
+        queue: VecDeque::new(),
+    };
+
+    // The inputs are random, but each side keeps moving the same way for a while like a person would,
+    // and every so often tries to serve.
+    let mut inputs = StdRng::seed_from_u64(settings.seed * 31 + side);
+    let mut direction = 0;
+    let mut stalls = 0;
+    let mut packet = [0; 512];
+    let mut last_heard = Instant::now();
+    let mut done: Option<Instant> = None;
+
+    loop {
+        thread::sleep(TICK_TIME);
+
+        loop {
+            let length = match socket.recv(&mut packet) {
+                Ok(length) => length,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => continue,
+                Err(err) => return Err(err),
+            };
+
+            last_heard = Instant::now();
+
+            match Message::decode(&packet[..length]) {
+                Ok(Message::Inputs { ack, start, inputs }) => {
+                    session.ack(ack);
+                    session.add_remote_inputs(start, &inputs);
+                }
+                Ok(Message::Checksum { frame, checksum }) => {
+                    session.add_remote_checksum(frame, checksum)
+                }
+                _ => (),
+            }
+        }
+
+        if last_heard.elapsed() > TIMEOUT {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the other peer stopped responding",
+            ));
+        }
+
+        if session.frame() < settings.frames {
+            if session.can_advance() {
+                if inputs.gen_range(0..4) == 0 {
+                    direction = inputs.gen_range(-1..=1);
+                }
+
+                session.advance(Input {
+                    direction,
+                    serve: inputs.gen_range(0..10) == 0,
+                });
+            } else {
+                stalls += 1;
+            }
+        }
+
+        let (start, unacked) = session.unacked_inputs();
+
+        link.send(&Message::Inputs {
+            ack: session.remote_frames(),
+            start,
+            inputs: unacked.to_vec(),
+        });
+
+        for (frame, checksum) in session.take_checksums() {
+            link.send(&Message::Checksum { frame, checksum });
+        }
+
+        link.flush();
+
+        if session.confirmed_frame() >= settings.frames && session.all_acked() {
+            match done {
+                Some(time) if time.elapsed() >= LINGER => break,
+                Some(_) => (),
+                None => done = Some(Instant::now()),
+            }
+        }
+    }
+
+    println!(
+        "{:016x} {} {} {}",
+        session.game().checksum(),
+        session.rollbacks,
+        stalls,
+        session.desynced
+    );
+
+    Ok(())
+}
@@ -1,32 +1,160 @@
-use rand::Rng;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    arena::Obstacle,
+    modifiers::{
+        Effect, PowerUp, PowerUpKind, EFFECT_DURATION, ENLARGE_ROWS, MAX_POWER_UPS,
+        POWER_UP_INTERVAL, POWER_UP_KINDS, SHRINK_ROWS, SPEED_UP_FACTOR, SPLIT_SLOPE,
+    },
+    paddle::Paddle,
+    rules::{MatchRules, ServeRule},
+    screen,
+    stats::Stats,
+    BALL_SPEED, BALL_SPEED_UP, MAX_BALLS, MAX_BALL_SPEED, MAX_BOUNCE_SLOPE, MAX_SERVE_SLOPE,
+    PADDLE_HEIGHT, PADDLE_PADDING, PADDLE_WIDTH, SERVE_TIMEOUT, SPIN, ZONE_SLOPE,
+};
+
+// How far a ball moves between each check for obstacles and power-ups along its path.
+const PATH_STEP: f32 = 0.5;
+
+// How steeply the balls served from the middle alongside the server's head off, in turn.
+const SERVE_SLOPES: [f32; 4] = [0.5, -0.5, 1.0, -1.0];
+
+#[derive(PartialEq, Eq)]
+pub enum TickStatus {
+    Ok,
+    Exit,
+}
 
-use crate::{screen, PADDLE_PADDING};
+// Players one and two are on the left and right. In a free-for-all, players three and four join in on the top and bottom.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Player {
+    One,
+    Two,
+    Three,
+    Four,
+}
 
-// Simplifies an angle to be from 1 to 360 degrees.
-// Favors 360 degrees instead of 0 degrees
-pub fn angle(raw: f32) -> f32 {
-    let mut result = raw;
+pub const PLAYERS: [Player; 4] = [Player::One, Player::Two, Player::Three, Player::Four];
 
-    while result >= 360.0 {
-        result -= 360.0
+impl Player {
+    // The player on the opposite side of the court.
+    pub fn other(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+            Player::Three => Player::Four,
+            Player::Four => Player::Three,
+        }
     }
 
-    while result <= 0.0 {
-        result += 360.0
+    // Where the player goes in anything listing all four of them, like `Game::lives`.
+    pub fn index(&self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+            Player::Three => 2,
+            Player::Four => 3,
+        }
     }
 
-    return result;
+    // Whether the player's paddle lies along the top or bottom of the court, and moves sideways.
+    pub fn horizontal(&self) -> bool {
+        matches!(self, Player::Three | Player::Four)
+    }
 }
 
+// A ball's position, and how far it moves every tick.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ball {
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+
+    // Whoever hit the ball last, who gets any power-up it runs into.
+    pub owner: Option<Player>,
+}
+
+impl Ball {
+    // How far the ball moves every tick, in any direction.
+    pub fn speed(&self) -> f32 {
+        self.velocity_x.hypot(self.velocity_y)
+    }
+}
+
+// Everything about a match, which plays out exactly the same way every time given the same seed and inputs.
+// That's what lets online play save a copy of it every tick and go back to one, see `net::rollback`.
+#[derive(Clone)]
 pub struct Game {
-    pub paddle_1_y: usize,
-    pub paddle_2_y: usize,
+    // Every player's paddle, in `Player::index` order.
+    // The top and bottom paddles only get used in a free-for-all.
+    pub paddles: [Paddle; 4],
+
+    // Who serves the current rally, or the next one if it's already been served.
+    pub server: Player,
+
+    // Every ball in play. Each one bounces and scores by itself, and they only get served again
+    // once the last one has left the court.
+    pub balls: Vec<Ball>,
+
+    pub rules: MatchRules,
+
+    // Points in the current game.
     pub score_1: u32,
     pub score_2: u32,
-    pub server: bool,
-    pub ball_x: f32,
-    pub ball_y: f32,
-    pub ball_dir: f32,
+
+    // Games won so far in the match.
+    pub games_1: u32,
+    pub games_2: u32,
+
+    // Points won over the whole match.
+    pub points_1: u32,
+    pub points_2: u32,
+
+    // How many times a ball has been hit in the current rally.
+    pub rally: u32,
+
+    // The length of every finished rally in the match.
+    pub rallies: Vec<u32>,
+
+    pub stats: Stats,
+
+    // Whether the balls are waiting for the server to serve them.
+    // The server holds the first ball against their paddle, and any others wait in the middle.
+    pub serving: bool,
+
+    // How many ticks are left before the server can serve.
+    pub serve_timer: u32,
+
+    // How many ticks the server has left to serve once they can, before the balls get served for them.
+    launch_timer: u32,
+
+    pub winner: Option<Player>,
+
+    // How many lives each player has left in a free-for-all, in `Player::index` order.
+    // Anyone who runs out is knocked out, and their side of the court turns into a wall.
+    // A normal match between players one and two is played for points instead, and leaves this as `None`.
+    pub lives: Option<[u32; 4]>,
+
+    // Power-ups waiting on the court, and the ones that have been picked up and haven't worn off yet.
+    // These only show up when the rules turn them on.
+    pub power_ups: Vec<PowerUp>,
+    pub effects: Vec<Effect>,
+
+    // How many ticks are left before the next power-up shows up.
+    power_up_timer: u32,
+
+    // Blocks the ball bounces off, when the match is played in an arena, see `arena::Arena`.
+    pub obstacles: Vec<Obstacle>,
+
+    // All the randomness in the game comes from here, so it's the same on every copy of it.
+    rng: StdRng,
 
     pub width: usize,
     width_f32: f32,
@@ -35,127 +163,843 @@ pub struct Game {
 }
 
 impl Game {
-    pub fn new(screen: &screen::Screen) -> Game {
-        Game {
-            paddle_1_y: 0,
-            paddle_2_y: 0,
-            ball_dir: 0.0,
-            ball_x: 0.0,
-            ball_y: 0.0,
-            server: false,
-            width: screen.width,
-            width_f32: screen.width as f32,
-            height: screen.height - 1,
-            height_f32: (screen.height - 1) as f32,
+    pub fn new(screen: &screen::Screen, rules: MatchRules, seed: u64) -> Game {
+        Game::with_size(screen.width, screen.height - 1, rules, seed)
+    }
 
+    // Makes a game with a specific playfield size, instead of using the screen's.
+    pub fn with_size(width: usize, height: usize, rules: MatchRules, seed: u64) -> Game {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // Whoever serves first is picked at random.
+        let server = if rng.gen() { Player::One } else { Player::Two };
+
+        // Every paddle starts out in the middle of its side.
+        let middle_row = Paddle::new((height / 2) as f32);
+        let middle_column = Paddle::new((width / 2) as f32);
+
+        Game {
+            paddles: [middle_row, middle_row, middle_column, middle_column],
+            balls: Vec::new(),
+            server,
+            width,
+            width_f32: width as f32,
+            height,
+            height_f32: height as f32,
+
+            rules,
             score_1: 0,
             score_2: 0,
+            games_1: 0,
+            games_2: 0,
+            points_1: 0,
+            points_2: 0,
+            rally: 0,
+            rallies: Vec::new(),
+            stats: Stats::default(),
+            serving: false,
+            serve_timer: 0,
+            launch_timer: 0,
+            winner: None,
+            lives: None,
+            power_ups: Vec::new(),
+            effects: Vec::new(),
+            power_up_timer: POWER_UP_INTERVAL,
+            obstacles: Vec::new(),
+            rng,
+        }
+    }
+
+    // Turns the match into a free-for-all between some players, who each start with `MatchRules::lives` lives.
+    // One of them gets picked at random to serve first.
+    // Any side without a player on it is a wall from the start.
+    pub fn free_for_all(&mut self, players: &[Player]) {
+        let mut lives = [0; 4];
+
+        for player in players {
+            lives[player.index()] = self.rules.lives;
+        }
+
+        self.lives = Some(lives);
+
+        if !players.is_empty() {
+            self.server = players[self.rng.gen_range(0..players.len())];
+        }
+    }
+
+    // Whether a player has a paddle on the court, rather than their side being a wall.
+    pub fn in_play(&self, player: Player) -> bool {
+        match self.lives {
+            Some(lives) => lives[player.index()] > 0,
+            None => !player.horizontal(),
         }
     }
 
+    // Gets the balls ready for `server` to serve, after a countdown.
+    // Chaos pong adds another ball every few rallies, see `MatchRules::chaos`.
     pub fn ball_initial_pos(&mut self) {
-        self.ball_x = self.width as f32 / 2.0;
-        self.ball_y = self.height as f32 / 2.0;
+        let count = match self.rules.chaos {
+            0 => 1,
+            every => (1 + self.rallies.len() / every as usize).min(MAX_BALLS),
+        };
+
+        // Any balls besides the server's go back and forth from the middle, starting with the side across from them.
+        // In a free-for-all, every player still in gets served to in turn instead.
+        let mut targets: Vec<Player> = match self.lives {
+            Some(_) => PLAYERS
+                .into_iter()
+                .filter(|player| self.in_play(*player))
+                .collect(),
+            None => vec![self.server.other(), self.server],
+        };
+
+        if targets.is_empty() {
+            targets.push(self.server.other());
+        }
 
-        if self.server {
-            self.ball_dir = angle(180.0);
-        } else {
-            self.ball_dir = angle(180.0);
+        if self.lives.is_some() {
+            let turn = self.rallies.len() % targets.len();
+
+            targets.rotate_left(turn);
+        }
+
+        self.balls = (0..count)
+            .map(|i| match i {
+                // The server's ball doesn't go anywhere until they serve it.
+                0 => Ball {
+                    x: 0.0,
+                    y: 0.0,
+                    velocity_x: 0.0,
+                    velocity_y: 0.0,
+                    owner: None,
+                },
+                _ => self.serve_towards(
+                    targets[(i - 1) % targets.len()],
+                    SERVE_SLOPES[(i - 1) % SERVE_SLOPES.len()],
+                ),
+            })
+            .collect();
+
+        self.serving = true;
+        self.serve_timer = self.rules.serve_delay;
+        self.launch_timer = SERVE_TIMEOUT;
+
+        self.hold_ball();
+    }
+
+    // Keeps the server's ball right in front of their paddle while they get ready to serve.
+    fn hold_ball(&mut self) {
+        let face = self.paddle_face(self.server);
+        let position = self.paddle(self.server).position;
+        let ball = &mut self.balls[0];
+
+        (ball.x, ball.y) = match self.server.horizontal() {
+            true => (position, face),
+            false => (face, position),
+        };
+    }
+
+    // Whether `player` is the one serving, and the countdown is over so they can.
+    pub fn can_serve(&self, player: Player) -> bool {
+        self.serving && self.serve_timer == 0 && self.server == player
+    }
+
+    // Serves the balls if it's `player`'s turn to, and the countdown's over.
+    pub fn serve(&mut self, player: Player) {
+        if self.can_serve(player) {
+            self.launch();
+        }
+    }
+
+    // Sends the server's ball off across the court at a random slope, along with any balls waiting in the middle.
+    fn launch(&mut self) {
+        let slope = self.rng.gen_range(-MAX_SERVE_SLOPE..=MAX_SERVE_SLOPE);
+        let served = self.serve_towards(self.server.other(), slope);
+        let ball = &mut self.balls[0];
+
+        (ball.velocity_x, ball.velocity_y) = (served.velocity_x, served.velocity_y);
+        ball.owner = Some(self.server);
+
+        self.serving = false;
+    }
+
+    // Who serves the next rally, after `loser` lost the last point of this one.
+    fn next_server(&self, loser: Player) -> Player {
+        match self.rules.serve {
+            ServeRule::Alternate => self.next_in_play(self.server),
+            ServeRule::Loser if self.in_play(loser) => loser,
+            // Someone who just got knocked out of a free-for-all can't serve, so it goes to whoever's next.
+            ServeRule::Loser => self.next_in_play(loser),
+        }
+    }
+
+    // The next player after `player` who's still in play, going around the court in `PLAYERS` order.
+    fn next_in_play(&self, player: Player) -> Player {
+        (1..=PLAYERS.len())
+            .map(|step| PLAYERS[(player.index() + step) % PLAYERS.len()])
+            .find(|player| self.in_play(*player))
+            .unwrap_or(player)
+    }
+
+    // A ball in the middle of the court, heading towards a player's side.
+    fn serve_towards(&self, player: Player, slope: f32) -> Ball {
+        let length = slope.hypot(1.0);
+        let across = BALL_SPEED / length;
+        let along = slope * BALL_SPEED / length;
+
+        let (velocity_x, velocity_y) = match player {
+            Player::One => (-across, along),
+            Player::Two => (across, along),
+            Player::Three => (along, -across),
+            Player::Four => (along, across),
+        };
+
+        Ball {
+            x: self.width as f32 / 2.0,
+            y: self.height as f32 / 2.0,
+            velocity_x,
+            velocity_y,
+            owner: None,
+        }
+    }
+
+    // The number of the game currently being played in the match, starting from 1.
+    pub fn game_number(&self) -> u32 {
+        self.games_1 + self.games_2 + 1
+    }
+
+    // Gives a point to a player, and works out whether that won them the game or the match.
+    // Returns whether the game is over.
+    fn point(&mut self, player: Player) -> bool {
+        let (score, other, points, games) = match player {
+            Player::One => (
+                &mut self.score_1,
+                &mut self.score_2,
+                &mut self.points_1,
+                &mut self.games_1,
+            ),
+            Player::Two => (
+                &mut self.score_2,
+                &mut self.score_1,
+                &mut self.points_2,
+                &mut self.games_2,
+            ),
+            // The top and bottom are only ever someone's side in a free-for-all, which goes by lives instead.
+            Player::Three | Player::Four => return false,
+        };
+
+        *score += 1;
+        *points += 1;
+
+        if self.rules.won_game(*score, *other) {
+            *games += 1;
+            *score = 0;
+            *other = 0;
+
+            if *games >= self.rules.games_to_win() {
+                self.winner = Some(player);
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    // Takes a life from a player in a free-for-all, and works out whether that leaves only one player standing.
+    // Returns whether the match is over.
+    fn lose_life(&mut self, player: Player) -> bool {
+        let Some(lives) = &mut self.lives else {
+            return false;
+        };
+
+        lives[player.index()] = lives[player.index()].saturating_sub(1);
+
+        let standing: Vec<Player> = PLAYERS
+            .into_iter()
+            .filter(|player| lives[player.index()] > 0)
+            .collect();
+
+        if let [winner] = standing[..] {
+            self.winner = Some(winner);
+
+            return true;
+        }
+
+        false
+    }
+
+    // Ends the current rally, which `loser` lost the last point of, and gets the balls ready to be served again.
+    fn end_rally(&mut self, loser: Player) {
+        self.rallies.push(self.rally);
+        self.rally = 0;
+        self.server = self.next_server(loser);
+
+        self.ball_initial_pos();
+    }
+
+    // The column the ball bounces back from when it hits paddle 1, which is the one right next to it.
+    pub fn paddle_1_face(&self) -> f32 {
+        (PADDLE_PADDING + 1) as f32
+    }
+
+    // The column the ball bounces back from when it hits paddle 2.
+    pub fn paddle_2_face(&self) -> f32 {
+        (self.width - PADDLE_PADDING - 2) as f32
+    }
+
+    // The row the ball bounces back from when it hits the top paddle.
+    pub fn paddle_3_face(&self) -> f32 {
+        1.0
+    }
+
+    // The row the ball bounces back from when it hits the bottom paddle.
+    pub fn paddle_4_face(&self) -> f32 {
+        self.height_f32 - 1.0
+    }
+
+    pub fn paddle_face(&self, player: Player) -> f32 {
+        match player {
+            Player::One => self.paddle_1_face(),
+            Player::Two => self.paddle_2_face(),
+            Player::Three => self.paddle_3_face(),
+            Player::Four => self.paddle_4_face(),
         }
+    }
 
-        self.server = !self.server;
+    pub fn paddle(&self, player: Player) -> &Paddle {
+        &self.paddles[player.index()]
     }
 
-    pub fn tick(&mut self) {
-        if self.ball_y.round() >= self.height_f32 {
-            self.ball_dir = angle(self.ball_dir - 180.0)
+    // How far the middle of a player's paddle can go, so that all of it stays on the court.
+    pub fn paddle_range(&self, player: Player) -> (f32, f32) {
+        let size = self.paddle_size(player) as f32;
+
+        // The bottom row of the court is `height`, but the last column is one before `width`.
+        let last = match player.horizontal() {
+            true => self.width_f32 - 1.0,
+            false => self.height_f32,
+        };
+
+        (size, last - size)
+    }
+
+    // How far a player's paddle reaches either side of its middle, which power-ups can change.
+    // The top and bottom paddles grow and shrink twice as much, the same way they're twice as wide.
+    pub fn paddle_size(&self, player: Player) -> usize {
+        let (mut size, step) = match player.horizontal() {
+            true => (PADDLE_WIDTH, PADDLE_WIDTH / PADDLE_HEIGHT),
+            false => (PADDLE_HEIGHT, 1),
+        };
+
+        if self.has_effect(PowerUpKind::Enlarge, player) {
+            size += ENLARGE_ROWS * step;
         }
 
-        if self.ball_y.round() <= 0.0 {
-            self.ball_dir = angle(self.ball_dir - 180.0)
+        if self.has_effect(PowerUpKind::Shrink, player) {
+            size -= SHRINK_ROWS * step;
         }
 
-        if self.ball_x.round() >= self.width_f32 - 1.0 {
-            self.score_1 += 1;
-            self.ball_initial_pos();
+        size
+    }
+
+    pub fn has_effect(&self, kind: PowerUpKind, player: Player) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| effect.applies(kind, player))
+    }
+
+    // How much further than usual every ball moves each tick.
+    fn speed_factor(&self) -> f32 {
+        if self
+            .effects
+            .iter()
+            .any(|effect| effect.kind == PowerUpKind::SpeedUp)
+        {
+            SPEED_UP_FACTOR
+        } else {
+            1.0
         }
+    }
 
-        if self.ball_x.round() <= 0.0 {
-            self.score_2 += 1;
-            self.ball_initial_pos();
+    pub fn tick(&mut self) -> TickStatus {
+        if self.winner.is_some() {
+            return TickStatus::Exit;
         }
 
-        const PADDLE_PADDING_F32: f32 = PADDLE_PADDING as f32;
+        // Power-ups keep wearing off while the ball waits to be served.
+        for effect in &mut self.effects {
+            effect.timer -= 1;
+        }
 
-        // Perform some neat trigonometry to move the ball in a certain direction that I don't understand :)
-        self.ball_y = self.ball_y + self.ball_dir.to_radians().sin();
+        self.effects.retain(|effect| effect.timer > 0);
 
-        let cos = self.ball_dir.to_radians().cos();
+        // Paddles can move while the ball waits to be served.
+        for player in PLAYERS {
+            let (min, max) = self.paddle_range(player);
+
+            self.paddles[player.index()].tick(min, max, paddle_scale(player));
+        }
+
+        // Nothing moves until the balls are served, except for the server's ball moving along with their paddle.
+        if self.serving {
+            if self.serve_timer > 0 {
+                self.serve_timer -= 1;
+            } else if self.launch_timer > 0 {
+                self.launch_timer -= 1;
+            } else {
+                // Nobody gets to hold up the match forever.
+                self.launch();
+            }
+
+            if self.serving {
+                self.hold_ball();
+            }
+
+            return TickStatus::Ok;
+        }
+
+        if self.rules.power_ups {
+            self.spawn_power_up();
+        }
+
+        let mut exits = Vec::new();
+        let mut i = 0;
+
+        // Any balls that get split off along the way are added on the end, and start moving straight away.
+        while i < self.balls.len() {
+            let mut ball = self.balls[i];
+
+            match self.move_ball(&mut ball) {
+                Some(side) => {
+                    self.balls.remove(i);
+                    exits.push(side);
+                }
+                None => {
+                    self.balls[i] = ball;
+                    i += 1;
+                }
+            }
+        }
+
+        // Every ball that leaves the court is worth a point, or costs a life in a free-for-all,
+        // but the rally only ends once they're all gone.
+        // Winning a game ends it straight away though, so the next game starts with a fresh serve.
+        let mut game_over = false;
+        let mut loser = None;
+
+        for side in exits {
+            loser = Some(side);
+            self.stats.point(self.server, side);
+            game_over = match self.lives {
+                Some(_) => self.lose_life(side),
+                None => self.point(side.other()),
+            };
+
+            if game_over {
+                break;
+            }
+        }
+
+        if let Some(loser) = loser {
+            if game_over || self.balls.is_empty() {
+                self.end_rally(loser);
+            }
+        }
 
-        if self.ball_dir >= 180.0 {
-            self.ball_x -= cos;
+        TickStatus::Ok
+    }
+
+    // Moves a ball along for a tick, bouncing it off anything in its way.
+    // If it left the court, the player whose side it went out on is returned.
+    fn move_ball(&mut self, ball: &mut Ball) -> Option<Player> {
+        let from = (ball.x, ball.y);
+        let speed_factor = self.speed_factor();
+
+        ball.x += ball.velocity_x * speed_factor;
+        ball.y += ball.velocity_y * speed_factor;
+
+        // The paddles are checked over the whole path the ball took this tick, so fast balls can't skip past them.
+        if ball.velocity_x < 0.0 {
+            self.collide(ball, Player::One, from, speed_factor);
         } else {
-            self.ball_x += cos;
+            self.collide(ball, Player::Two, from, speed_factor);
         }
 
-        let offset;
-        let ball_rounded = self.ball_y.ceil() as i16;
+        if ball.velocity_y < 0.0 {
+            self.collide(ball, Player::Three, from, speed_factor);
+        } else if ball.velocity_y > 0.0 {
+            self.collide(ball, Player::Four, from, speed_factor);
+        }
 
-        // Collided with paddle 1
-        let collided_1 = self.ball_x.floor() == PADDLE_PADDING_F32 + 1.0;
+        self.stats.speed(ball.speed() * speed_factor);
 
-        // Collided with paddle 2
-        let collided_2 = self.ball_x.floor() == self.width_f32 - PADDLE_PADDING_F32 - 3.0;
+        self.bounce_off_walls(ball);
+        self.bounce_off_obstacles(ball, from);
+        self.pick_up_power_ups(ball, from);
 
-        if collided_1 {
-            offset = self.paddle_1_y as i16 - ball_rounded;
-        } else if collided_2 {
-            offset = self.paddle_2_y as i16 - ball_rounded;
+        if ball.x < 0.0 {
+            Some(Player::One)
+        } else if ball.x > self.width_f32 - 1.0 {
+            Some(Player::Two)
+        } else if ball.y < 0.0 {
+            Some(Player::Three)
+        } else if ball.y > self.height_f32 {
+            Some(Player::Four)
         } else {
+            None
+        }
+    }
+
+    // Bounces a ball back if it crossed a player's paddle after moving from `from` this tick.
+    // Every side works the same way, by looking at the ball's movement across the paddle's face and along it.
+    fn collide(&mut self, ball: &mut Ball, player: Player, from: (f32, f32), speed_factor: f32) {
+        if !self.in_play(player) {
+            return;
+        }
+
+        let face = self.paddle_face(player);
+        let paddle = *self.paddle(player);
+
+        let (along, velocity_across, velocity_along, from_across) = match player.horizontal() {
+            true => (ball.x, ball.velocity_y, ball.velocity_x, from.1),
+            false => (ball.y, ball.velocity_x, ball.velocity_y, from.0),
+        };
+
+        // How far through this tick's movement the ball crossed the paddle's face, from 0 to 1.
+        let crossed = (face - from_across) / (velocity_across * speed_factor);
+
+        if !(0.0..=1.0).contains(&crossed) {
             return;
         }
 
-        let mut ball_angle;
+        let hit = along - velocity_along * speed_factor * (1.0 - crossed);
+
+        // Which part of the paddle got hit, with 0 being the middle.
+        let offset = hit.round() - paddle.position.round();
 
-        match offset {
-            -2 => ball_angle = 45.0,
-            -1 => ball_angle = 22.5,
-            0 => ball_angle = 180.0,
-            1 => ball_angle = 180.0 + 22.5,
-            2 => ball_angle = 180.0 + 45.0,
-            _ => return,
+        if offset.abs() > self.paddle_size(player) as f32 {
+            return;
         }
 
-        if collided_2 {
-            ball_angle = 180.0 - ball_angle;
+        self.rally += 1;
+        self.stats.hit(player, offset, self.paddle_size(player));
+
+        // The top and bottom paddles are wider, so every column counts for less.
+        let scale = 1.0 / paddle_scale(player);
+
+        // Hitting further from the middle of the paddle sends the ball off at a steeper angle,
+        // and a moving paddle drags the ball along with it.
+        let jitter = self.rng.gen_range(-0.1..0.1);
+        let slope = ((offset * ZONE_SLOPE + paddle.velocity * SPIN) * scale + jitter)
+            .clamp(-MAX_BOUNCE_SLOPE, MAX_BOUNCE_SLOPE);
+
+        let speed = (ball.speed() + BALL_SPEED_UP).min(MAX_BALL_SPEED);
+        let direction = match player {
+            Player::One | Player::Three => 1.0,
+            Player::Two | Player::Four => -1.0,
+        };
+        let length = slope.hypot(1.0);
+
+        let velocity_across = direction * speed / length;
+        let velocity_along = slope * speed / length;
+
+        // Whatever movement was left after hitting the paddle carries on in the new direction.
+        let across = face + velocity_across * speed_factor * (1.0 - crossed);
+        let along = hit + velocity_along * speed_factor * (1.0 - crossed);
+
+        match player.horizontal() {
+            true => {
+                (ball.x, ball.y) = (along, across);
+                (ball.velocity_x, ball.velocity_y) = (velocity_along, velocity_across);
+            }
+            false => {
+                (ball.x, ball.y) = (across, along);
+                (ball.velocity_x, ball.velocity_y) = (velocity_across, velocity_along);
+            }
         }
 
-        let mut rng = rand::thread_rng();
+        ball.owner = Some(player);
+    }
+
+    // Reflects a ball off any side of the court that's a wall, rather than someone's goal.
+    fn bounce_off_walls(&self, ball: &mut Ball) {
+        (ball.x, ball.velocity_x) = bounce(
+            ball.x,
+            ball.velocity_x,
+            self.width_f32 - 1.0,
+            !self.in_play(Player::One),
+            !self.in_play(Player::Two),
+        );
+        (ball.y, ball.velocity_y) = bounce(
+            ball.y,
+            ball.velocity_y,
+            self.height_f32,
+            !self.in_play(Player::Three),
+            !self.in_play(Player::Four),
+        );
+    }
+
+    // Bounces a ball back off the first obstacle it ran into after moving from `from` this tick.
+    fn bounce_off_obstacles(&self, ball: &mut Ball, from: (f32, f32)) {
+        let mut previous = from;
+
+        for (x, y) in path(from, (ball.x, ball.y)) {
+            let Some(obstacle) = self
+                .obstacles
+                .iter()
+                .find(|obstacle| obstacle.contains(x, y))
+            else {
+                previous = (x, y);
+                continue;
+            };
 
-        let rand_offset: f32 = rng.gen_range(-10..10) as f32;
+            // If moving sideways is what put the ball inside, it hit the side of the obstacle,
+            // and if it was moving up or down, it hit the top or bottom. Otherwise it went straight into a corner.
+            let side = obstacle.contains(x, previous.1);
+            let end = obstacle.contains(previous.0, y);
 
-        ball_angle += rand_offset;
+            if side || !end {
+                ball.velocity_x = -ball.velocity_x;
+            }
 
-        self.ball_dir = angle(ball_angle);
+            if end || !side {
+                ball.velocity_y = -ball.velocity_y;
+            }
+
+            // The ball goes back to the last spot it was free, and carries on from there next tick.
+            (ball.x, ball.y) = previous;
+
+            return;
+        }
     }
 
-    pub fn move_paddle_1(&mut self, amount: i16) {
-        let new = self.paddle_1_y as i16 + amount;
+    // Every so often, puts a new power-up somewhere in the middle half of the court.
+    fn spawn_power_up(&mut self) {
+        if self.power_up_timer > 0 {
+            self.power_up_timer -= 1;
 
-        if new <= 0 && new >= self.height as i16 {
             return;
         }
 
-        self.paddle_1_y = new as usize;
-    }
+        self.power_up_timer = POWER_UP_INTERVAL;
+
+        if self.power_ups.len() >= MAX_POWER_UPS || self.width < 4 || self.height < 2 {
+            return;
+        }
+
+        let kind = POWER_UP_KINDS[self.rng.gen_range(0..POWER_UP_KINDS.len())];
+        let x = self.rng.gen_range(self.width / 4..self.width * 3 / 4);
+        let y = self.rng.gen_range(1..self.height);
 
-    pub fn move_paddle_2(&mut self, amount: i16) {
-        let new = self.paddle_2_y as i16 + amount;
+        // One that would land inside an obstacle just doesn't show up this time.
+        if self
+            .obstacles
+            .iter()
+            .any(|obstacle| obstacle.contains(x as f32, y as f32))
+        {
+            return;
+        }
+
+        self.power_ups.push(PowerUp { kind, x, y });
+    }
 
-        if new <= 0 && new >= self.height as i16 {
+    // Gives any power-ups a ball ran into after moving from `from` this tick to whoever hit it.
+    // A ball that hasn't been hit yet since the serve goes straight through them.
+    fn pick_up_power_ups(&mut self, ball: &Ball, from: (f32, f32)) {
+        let Some(player) = ball.owner else {
             return;
+        };
+
+        let mut picked_up = Vec::new();
+
+        self.power_ups.retain(|power_up| {
+            let touched = path(from, (ball.x, ball.y))
+                .any(|(x, y)| x.round() == power_up.x as f32 && y.round() == power_up.y as f32);
+
+            if touched {
+                picked_up.push(*power_up);
+            }
+
+            !touched
+        });
+
+        for power_up in picked_up {
+            match power_up.kind {
+                // Two new balls split off where the power-up was, heading the same way as the ball, one up and one down.
+                // Once there are too many balls, this doesn't do anything.
+                PowerUpKind::MultiBall => {
+                    let speed = ball.speed();
+                    let length = SPLIT_SLOPE.hypot(1.0);
+
+                    for slope in [-SPLIT_SLOPE, SPLIT_SLOPE] {
+                        if self.balls.len() >= MAX_BALLS {
+                            break;
+                        }
+
+                        self.balls.push(Ball {
+                            x: power_up.x as f32,
+                            y: power_up.y as f32,
+                            velocity_x: ball.velocity_x.signum() * speed / length,
+                            velocity_y: slope * speed / length,
+                            owner: ball.owner,
+                        });
+                    }
+                }
+                kind => {
+                    let target = match kind.target(player) {
+                        // In a free-for-all the player across the court might already be out,
+                        // so it goes to someone who's still in instead.
+                        Some(target) if target != player && !self.in_play(target) => {
+                            match self.opponent(player) {
+                                Some(opponent) => Some(opponent),
+                                None => continue,
+                            }
+                        }
+                        target => target,
+                    };
+
+                    // Getting one that's already going just makes it last longer.
+                    self.effects
+                        .retain(|effect| effect.kind != kind || effect.target != target);
+                    self.effects.push(Effect {
+                        kind,
+                        target,
+                        timer: EFFECT_DURATION,
+                    });
+                }
+            }
+        }
+    }
+
+    // Picks one of the players still in play other than `player`, or `None` if there aren't any.
+    fn opponent(&mut self, player: Player) -> Option<Player> {
+        let opponents: Vec<Player> = PLAYERS
+            .into_iter()
+            .filter(|&other| other != player && self.in_play(other))
+            .collect();
+
+        if opponents.is_empty() {
+            return None;
+        }
+
+        Some(opponents[self.rng.gen_range(0..opponents.len())])
+    }
+
+    // A hash of everything that changes while the game is played, to check two copies of it haven't drifted apart.
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        (self.paddles, self.server, self.lives).hash(&mut hasher);
+
+        for ball in &self.balls {
+            for number in [ball.x, ball.y, ball.velocity_x, ball.velocity_y] {
+                number.to_bits().hash(&mut hasher);
+            }
+
+            ball.owner.hash(&mut hasher);
+        }
+
+        (&self.power_ups, &self.effects, self.power_up_timer).hash(&mut hasher);
+        self.obstacles.hash(&mut hasher);
+
+        (self.score_1, self.score_2, self.games_1, self.games_2).hash(&mut hasher);
+        (self.points_1, self.points_2, self.rally, &self.rallies).hash(&mut hasher);
+        (self.stats.hits, self.stats.served, self.stats.won_on_serve).hash(&mut hasher);
+        self.stats.top_speed.to_bits().hash(&mut hasher);
+        (
+            self.serving,
+            self.serve_timer,
+            self.launch_timer,
+            self.winner,
+        )
+            .hash(&mut hasher);
+
+        // The generator's state can't be hashed directly, but the next number it gives says the same thing.
+        self.rng.clone().gen::<u64>().hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    // A random amount up to `amount` either way, for anything outside the game that needs one, like the CPU's aim.
+    // It comes out of the game's own generator, so a CPU misses the same way every time the same seed is played.
+    pub fn jitter(&mut self, amount: f32) -> f32 {
+        self.rng.gen_range(-amount..=amount)
+    }
+
+    // Pushes a player's paddle one way for this tick, like holding down its key.
+    pub fn push_paddle(&mut self, player: Player, direction: i8) {
+        let direction = self.controls(player, direction);
+
+        self.paddles[player.index()].push(direction);
+    }
+
+    // Pushes a player's paddle one way for just the next tick, see `Paddle::nudge`.
+    pub fn nudge_paddle(&mut self, player: Player, direction: i8) {
+        let direction = self.controls(player, direction);
+
+        self.paddles[player.index()].nudge(direction);
+    }
+
+    // Flips which way a player's paddle goes while their controls are reversed.
+    fn controls(&self, player: Player, direction: i8) -> i8 {
+        if self.has_effect(PowerUpKind::Reverse, player) {
+            -direction
+        } else {
+            direction
+        }
+    }
+}
+
+// How many times further a player's paddle moves than the left and right paddles do.
+// The top and bottom paddles move sideways, where tiles are about half as big, and their sides are longer too.
+pub fn paddle_scale(player: Player) -> f32 {
+    match player.horizontal() {
+        true => PADDLE_WIDTH as f32 / PADDLE_HEIGHT as f32,
+        false => 1.0,
+    }
+}
+
+// Bounces a ball back inside from 0 to `last` along one direction, off whichever ends are walls.
+// A fast ball on a small court can bounce off both ends in one tick, so it keeps going until it's back inside,
+// unless it's gone out past an end that isn't a wall.
+fn bounce(
+    mut position: f32,
+    mut velocity: f32,
+    last: f32,
+    low_wall: bool,
+    high_wall: bool,
+) -> (f32, f32) {
+    // A court with no room to bounce in would never get the ball back inside.
+    if last <= 0.0 && low_wall && high_wall {
+        return (0.0, velocity);
+    }
+
+    loop {
+        if position < 0.0 && low_wall {
+            position = -position;
+        } else if position > last && high_wall {
+            position = last * 2.0 - position;
+        } else {
+            return (position, velocity);
         }
 
-        self.paddle_2_y = new as usize;
+        velocity = -velocity;
     }
 }
+
+// Points along the path a ball took this tick, a short step apart, so it can't skip over anything small.
+fn path(from: (f32, f32), to: (f32, f32)) -> impl Iterator<Item = (f32, f32)> {
+    let steps = ((to.0 - from.0).hypot(to.1 - from.1) / PATH_STEP)
+        .ceil()
+        .max(1.0) as u32;
+
+    (1..=steps).map(move |step| {
+        let along = step as f32 / steps as f32;
+
+        (
+            from.0 + (to.0 - from.0) * along,
+            from.1 + (to.1 - from.1) * along,
+        )
+    })
+}
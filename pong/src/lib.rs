@@ -1,16 +1,123 @@
+pub mod ai;
+pub mod arena;
 pub mod game;
+pub mod menu;
+pub mod modifiers;
+pub mod net;
+pub mod paddle;
+pub mod play;
+pub mod rules;
 pub mod screen;
+pub mod spectate;
+pub mod stats;
+
+pub use play::run;
 
 use std::time::Duration;
 
+use ai::Difficulty;
+use game::Player;
+use session::EndReason;
+
 pub const RENDER_TIME: Duration = Duration::from_millis(10);
 pub const TICK_TIME: Duration = Duration::from_millis(60);
 
 pub const PADDLE_PADDING: usize = 5;
 pub const PADDLE_HEIGHT: usize = 2;
+// How far the top and bottom paddles reach either side of their middle in a free-for-all.
+// Their sides of the court are a lot longer, so they're wider than the left and right paddles are tall to make up for it.
+pub const PADDLE_WIDTH: usize = 6;
+
+// How many columns the ball moves every tick when it's served.
+pub const BALL_SPEED: f32 = 1.0;
+// The steepest a serve can go, either way. Every serve goes off at a random slope up to this.
+pub const MAX_SERVE_SLOPE: f32 = 0.5;
+// How many ticks the server gets to serve once the countdown's over, before the ball gets served for them.
+// About 6 seconds with a 60 ms tick.
+pub const SERVE_TIMEOUT: u32 = 100;
+// How much faster the ball gets every time it's hit.
+pub const BALL_SPEED_UP: f32 = 0.05;
+pub const MAX_BALL_SPEED: f32 = 2.5;
+// The most balls that can be in play at once.
+pub const MAX_BALLS: usize = 5;
+
+// How much steeper the ball bounces off for every row away from the middle of the paddle it hits.
+pub const ZONE_SLOPE: f32 = 0.4;
+// How much steeper the ball bounces off for every row per tick the paddle is moving at when it hits.
+pub const SPIN: f32 = 0.25;
+// The steepest the ball can go, so it never ends up bouncing straight up and down.
+pub const MAX_BOUNCE_SLOPE: f32 = 1.5;
+
+// What's handed back once a game is over, so whoever started it can decide what to do next.
+// The scores are the points each player won over the last match, and the reason is how that match ended.
+#[derive(Clone, Copy, Debug)]
+pub struct GameResult {
+    pub score_1: u32,
+    pub score_2: u32,
+    // The most points player 1 won in any match against the CPU, if they played one.
+    // That's the only mode where there's one person playing and points to count, so it's the only one with a best score.
+    pub best: Option<u32>,
+    pub reason: EndReason,
+}
+
+// Who's in control of paddle 2.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    TwoPlayer,
+    Cpu(Difficulty),
+    // Someone playing from another computer, see the `net` module.
+    Online,
+    // Up to four players on every side of the court, each one on lives, see `Game::free_for_all`.
+    // The seats are in `Player::index` order.
+    FreeForAll([Seat; 4]),
+}
+
+// Who sits on one side of the court in a free-for-all.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Seat {
+    Human,
+    Cpu,
+    // Nobody, so that side is a wall.
+    Empty,
+}
+
+impl Seat {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Seat::Human => "Human",
+            Seat::Cpu => "CPU",
+            Seat::Empty => "Empty",
+        }
+    }
 
-pub fn exit(exit_message: &str) {
-    println!("\r\x1b[2J\r\x1b[H\x1b[?25h{exit_message}");
+    // The next kind of seat, for flicking through them in the setup screen.
+    pub fn next(&self) -> Seat {
+        match self {
+            Seat::Human => Seat::Cpu,
+            Seat::Cpu => Seat::Empty,
+            Seat::Empty => Seat::Human,
+        }
+    }
+}
+
+impl Mode {
+    // What a player gets called on screen.
+    pub fn player_name(&self, player: Player) -> &'static str {
+        match (self, player) {
+            (Mode::Online, Player::One) => "Host",
+            (Mode::Online, Player::Two) => "Guest",
+            (Mode::Cpu(_), Player::Two) => "CPU",
+            (Mode::FreeForAll(seats), player) if seats[player.index()] == Seat::Cpu => {
+                ["CPU 1", "CPU 2", "CPU 3", "CPU 4"][player.index()]
+            }
+            (_, player) => ["Player 1", "Player 2", "Player 3", "Player 4"][player.index()],
+        }
+    }
+}
 
-    std::process::exit(0)
+// Unlike the other games, pong's render thread can be sent a note as well as being stopped.
+pub enum ChannelMessage {
+    Stop,
+    // Sets some extra text to show in the status bar.
+    Note(String),
 }
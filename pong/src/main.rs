@@ -1,103 +1,175 @@
 use std::{
-    sync::{Arc, Mutex},
-    thread,
+    env,
+    net::{Ipv4Addr, SocketAddr},
+    process::exit,
 };
 
 use console::Term;
 use pong::{
-    exit, game,
-    screen::{BgColor, FgColor, Screen, Tile},
-    PADDLE_HEIGHT, PADDLE_PADDING, RENDER_TIME, TICK_TIME,
+    arena::Arena,
+    net,
+    rules::{MatchRules, ServeRule},
+    spectate,
 };
+use session::cleanup;
 
 fn main() {
+    let mut rules = MatchRules::default();
+    let mut arena = None;
+    let mut args = env::args().skip(1);
+
+    // Set when playing online instead of on one keyboard.
+    let mut host = None;
+    let mut connect = None;
+    let mut delay = net::DEFAULT_INPUT_DELAY;
+
+    // Where to save the stats for every finished match, if anywhere.
+    let mut export = None;
+
+    // Set when letting other people watch, or when watching someone else.
+    let mut broadcast = None;
+    let mut watch = None;
+
+    // Set when playing today's daily challenge, where every match is served the same way as everyone else's.
+    let mut seed = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--points" => rules.points_to_win = number(&arg, args.next()),
+            "--win-by" => rules.win_by = number(&arg, args.next()),
+            "--best-of" => rules.best_of = number(&arg, args.next()),
+            "--power-ups" => rules.power_ups = true,
+            "--chaos" => rules.chaos = number(&arg, args.next()),
+            "--serve" => match args.next().as_deref() {
+                Some("alternate") => rules.serve = ServeRule::Alternate,
+                Some("loser") => rules.serve = ServeRule::Loser,
+                _ => usage(&format!("{arg} needs to be either alternate or loser")),
+            },
+            "--arena" => match args.next().map(|path| Arena::load(&path)) {
+                Some(Ok(map)) => arena = Some(map),
+                Some(Err(err)) => usage(&format!("Couldn't load the arena: {err}")),
+                None => usage(&format!("{arg} needs a map file, like arenas/pillars.txt")),
+            },
+            "--delay" => match args.next().as_deref().map(str::parse) {
+                Some(Ok(frames)) => delay = frames,
+                _ => usage(&format!("{arg} needs a number of frames, like 2")),
+            },
+            "--host" => host = Some(port(&arg, args.next())),
+            "--connect" => match args.next() {
+                Some(addr) => connect = Some(addr),
+                None => usage(&format!("{arg} needs an address, like 127.0.0.1:7777")),
+            },
+            "--stats" => match args.next() {
+                Some(path) => export = Some(path),
+                None => usage(&format!("{arg} needs a file, like ladder.jsonl")),
+            },
+            // Spectators have to be on the same computer, unless the game's explicitly shared on another address.
+            "--broadcast" => {
+                let port = port(&arg, args.next());
+
+                broadcast = Some(SocketAddr::from((Ipv4Addr::LOCALHOST, port)));
+            }
+            "--broadcast-on" => match args.next().as_deref().map(str::parse) {
+                Some(Ok(addr)) => broadcast = Some(addr),
+                _ => usage(&format!("{arg} needs an address, like 0.0.0.0:7778")),
+            },
+            "--watch" => match args.next() {
+                Some(addr) => watch = Some(addr),
+                None => usage(&format!("{arg} needs an address, like 127.0.0.1:7778")),
+            },
+            "--daily" => seed = Some(daily::today_seed()),
+            _ => usage(&format!("Unknown argument: {arg}")),
+        }
+    }
+
+    if host.is_some() && connect.is_some() {
+        usage("You can't host and connect at the same time");
+    }
+
+    // Online matches are seeded by the host during the handshake, so there's no daily challenge to play.
+    if seed.is_some() && (host.is_some() || connect.is_some()) {
+        usage("The daily challenge can't be played online");
+    }
+
     let term = Term::stdout();
-    let mut screen = Screen::new(&term);
-    let mut game = game::Game::new(&screen);
-
-    game.paddle_1_y = screen.height / 2;
-    game.paddle_2_y = screen.height / 2;
-    game.ball_initial_pos();
-
-    let game = Arc::new(Mutex::new(game));
-
-    term.hide_cursor().unwrap();
-
-    // Renders the game every 10 ms.
-    let game_mutex = Arc::clone(&game);
-    let render_thread = thread::spawn(move || loop {
-        thread::sleep(RENDER_TIME);
-
-        screen.reset();
-
-        let game = game_mutex.lock().unwrap();
-
-        screen.rectangle(
-            PADDLE_PADDING,
-            game.paddle_1_y - PADDLE_HEIGHT,
-            PADDLE_PADDING + 1,
-            game.paddle_1_y + PADDLE_HEIGHT + 1,
-            Tile::new(FgColor::Default, BgColor::Red, b'|'),
-        );
-        screen.rectangle(
-            game.width - PADDLE_PADDING - 1,
-            game.paddle_2_y - PADDLE_HEIGHT,
-            game.width - PADDLE_PADDING,
-            game.paddle_2_y + PADDLE_HEIGHT + 1,
-            Tile::new(FgColor::Default, BgColor::Blue, b'|'),
-        );
-
-        // TODO: Rounding's a bit weird, so maybe consider changing this later.
-        screen.set(
-            game.ball_x.round() as usize,
-            game.ball_y.round() as usize,
-            Tile::new(FgColor::Default, BgColor::Default, b'o'),
-        );
-
-        let player_1_score_display = format!(
-            "Angle: {}, Ball X: {}, Player 1 score: {}",
-            game.ball_dir, game.ball_x, game.score_1
-        );
-        let player_2_score_display = format!("Player 2 score: {}", game.score_2);
-
-        screen.render(&format!(
-            "{}{}{}",
-            player_1_score_display,
-            " ".repeat(game.width - player_1_score_display.len() - player_2_score_display.len()),
-            player_2_score_display
-        ));
-    });
-
-    // Gathers input in a seperate thread.
-    let game_mutex = Arc::clone(&game);
-    let input_thread = thread::spawn(move || loop {
-        let key = term
-            .read_key()
-            .expect("an error occurred while reading input");
-
-        let mut game = game_mutex.lock().unwrap();
-
-        match key {
-            console::Key::Char('w') => game.move_paddle_1(-1),
-            console::Key::Char('s') => game.move_paddle_1(1),
-            console::Key::ArrowUp => game.move_paddle_2(-1),
-            console::Key::ArrowDown => game.move_paddle_2(1),
-            console::Key::Char('q') => exit("Quit."),
-            _ => (),
+
+    if let Some(addr) = watch {
+        match spectate::watch(&term, &addr) {
+            Ok(()) => cleanup("Stopped watching."),
+            Err(err) => {
+                cleanup(&format!("Couldn't keep watching: {err}"));
+
+                exit(1);
+            }
         }
-    });
 
-    // The game thread, it ticks every 100 ms.
-    let game_mutex = Arc::clone(&game);
-    let game_thread = thread::spawn(move || loop {
-        thread::sleep(TICK_TIME);
+        return;
+    }
+
+    let spectators = match broadcast.map(spectate::start) {
+        Some(Ok(server)) => Some(server),
+        Some(Err(err)) => usage(&format!("Couldn't start broadcasting: {err}")),
+        None => None,
+    };
+
+    let result = match (host, connect) {
+        (Some(port), _) => net::host(
+            &term,
+            port,
+            rules,
+            arena.as_ref(),
+            delay,
+            spectators,
+            export.as_deref(),
+        ),
+        (_, Some(addr)) => net::connect(&term, &addr, delay, spectators, export.as_deref()),
+        _ => Ok(pong::run(
+            &term,
+            rules,
+            arena.as_ref(),
+            spectators,
+            export.as_deref(),
+            seed,
+        )),
+    };
+
+    match result {
+        Ok(result) => cleanup(&format!(
+            "Player 1 points: {}, Player 2 points: {}",
+            result.score_1, result.score_2
+        )),
+        Err(err) => {
+            cleanup(&format!("Couldn't play online: {err}"));
+
+            exit(1);
+        }
+    }
+}
+
+// Prints what went wrong with the arguments, and how to use them.
+fn usage(problem: &str) -> ! {
+    eprintln!("{problem}");
+    eprintln!("Usage: pong [--points <n>] [--win-by <n>] [--best-of <n>] [--power-ups] [--chaos <rallies>] [--serve <alternate | loser>] [--arena <file>] [--stats <file>] [--daily] [--host <port> | --connect <addr>] [--delay <frames>] [--broadcast <port> | --broadcast-on <addr> | --watch <addr>]");
+
+    exit(1);
+}
 
-        let mut game = game_mutex.lock().unwrap();
+// Parses the port given to a flag.
+fn port(flag: &str, value: Option<String>) -> u16 {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(port)) => port,
+        _ => usage(&format!("{flag} needs a port, like 7777")),
+    }
+}
 
-        game.tick()
-    });
+// Parses the value given to a flag, which has to be a number bigger than zero.
+fn number(flag: &str, value: Option<String>) -> u32 {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(number)) if number > 0 => number,
+        _ => {
+            eprintln!("{flag} needs a number bigger than zero");
 
-    render_thread.join().unwrap();
-    input_thread.join().unwrap();
-    game_thread.join().unwrap();
+            exit(1);
+        }
+    }
 }
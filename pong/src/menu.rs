@@ -0,0 +1,104 @@
+use crate::{ai::Difficulty, Mode, Seat};
+
+pub const DEFAULT_MENU: MenuData = MenuData {
+    options: [
+        MenuOption::Play(Mode::Cpu(Difficulty::Easy)),
+        MenuOption::Play(Mode::Cpu(Difficulty::Medium)),
+        MenuOption::Play(Mode::Cpu(Difficulty::Hard)),
+        MenuOption::Play(Mode::TwoPlayer),
+        MenuOption::Play(Mode::FreeForAll(DEFAULT_SEATS.seats)),
+    ],
+    cursor_index: 0,
+};
+
+// One person against three CPUs, until someone changes it.
+pub const DEFAULT_SEATS: SeatMenu = SeatMenu {
+    seats: [Seat::Human, Seat::Cpu, Seat::Cpu, Seat::Cpu],
+    cursor_index: 0,
+};
+
+// What each side of the court is called in the seat menu, along with the keys for playing on it.
+pub const SEAT_NAMES: [&str; 4] = [
+    "Left (w/s, d)",
+    "Right (arrows)",
+    "Top (z/x, c)",
+    "Bottom (n/m, b)",
+];
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MenuOption {
+    Play(Mode),
+}
+
+impl MenuOption {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            MenuOption::Play(Mode::Cpu(Difficulty::Easy)) => "1 Player (Easy)",
+            MenuOption::Play(Mode::Cpu(Difficulty::Medium)) => "1 Player (Medium)",
+            MenuOption::Play(Mode::Cpu(Difficulty::Hard)) => "1 Player (Hard)",
+            MenuOption::Play(Mode::TwoPlayer) => "2 Players",
+            MenuOption::Play(Mode::FreeForAll(_)) => "4 Players",
+            // Online matches are started with `--host` or `--connect`, rather than from the menu.
+            MenuOption::Play(Mode::Online) => "Online",
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct MenuData {
+    pub options: [MenuOption; 5],
+    pub cursor_index: usize,
+}
+
+impl MenuData {
+    pub fn up(&mut self) {
+        if self.cursor_index > 0 {
+            self.cursor_index -= 1;
+        }
+    }
+
+    pub fn down(&mut self) {
+        if self.cursor_index < self.options.len() - 1 {
+            self.cursor_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> MenuOption {
+        self.options[self.cursor_index]
+    }
+}
+
+// Picks who sits on each side of the court before a free-for-all.
+#[derive(PartialEq)]
+pub struct SeatMenu {
+    pub seats: [Seat; 4],
+    pub cursor_index: usize,
+}
+
+impl SeatMenu {
+    pub fn up(&mut self) {
+        if self.cursor_index > 0 {
+            self.cursor_index -= 1;
+        }
+    }
+
+    pub fn down(&mut self) {
+        if self.cursor_index < self.seats.len() - 1 {
+            self.cursor_index += 1;
+        }
+    }
+
+    // Changes who's sitting in the selected seat.
+    pub fn change(&mut self) {
+        self.seats[self.cursor_index] = self.seats[self.cursor_index].next();
+    }
+
+    // A free-for-all needs at least two players in it.
+    pub fn ready(&self) -> bool {
+        self.seats
+            .iter()
+            .filter(|seat| **seat != Seat::Empty)
+            .count()
+            >= 2
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    game::Player,
+    screen::{BgColor, FgColor, Tile},
+};
+
+// How many ticks pass between power-ups showing up, about 8 seconds with a 60 ms tick.
+pub const POWER_UP_INTERVAL: u32 = 130;
+
+// The most power-ups that can be waiting on the court at once.
+pub const MAX_POWER_UPS: usize = 2;
+
+// How many ticks a power-up lasts once it's picked up, about 10 seconds with a 60 ms tick.
+pub const EFFECT_DURATION: u32 = 170;
+
+// How much faster the ball goes while it's sped up.
+pub const SPEED_UP_FACTOR: f32 = 1.5;
+
+// How many rows a paddle grows or shrinks by, on each side of its middle.
+pub const ENLARGE_ROWS: usize = 2;
+pub const SHRINK_ROWS: usize = 1;
+
+// How steeply the balls split off by a multi-ball power-up head up and down.
+pub const SPLIT_SLOPE: f32 = 0.6;
+
+// What a power-up does once the ball runs into it.
+// The player who last hit the ball is the one who gets it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PowerUpKind {
+    // Makes their paddle bigger.
+    Enlarge,
+    // Makes the other player's paddle smaller.
+    Shrink,
+    // Splits off two more balls.
+    MultiBall,
+    // Makes every ball go faster.
+    SpeedUp,
+    // Swaps up and down for the other player.
+    Reverse,
+}
+
+pub const POWER_UP_KINDS: [PowerUpKind; 5] = [
+    PowerUpKind::Enlarge,
+    PowerUpKind::Shrink,
+    PowerUpKind::MultiBall,
+    PowerUpKind::SpeedUp,
+    PowerUpKind::Reverse,
+];
+
+impl PowerUpKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            PowerUpKind::Enlarge => "Big",
+            PowerUpKind::Shrink => "Small",
+            PowerUpKind::MultiBall => "Multi-ball",
+            PowerUpKind::SpeedUp => "Fast",
+            PowerUpKind::Reverse => "Reversed",
+        }
+    }
+
+    // How the power-up looks while it's waiting on the court.
+    pub fn tile(&self) -> Tile {
+        match self {
+            PowerUpKind::Enlarge => Tile::new(FgColor::Black, BgColor::Green, b'+'),
+            PowerUpKind::Shrink => Tile::new(FgColor::Black, BgColor::Red, b'-'),
+            PowerUpKind::MultiBall => Tile::new(FgColor::Black, BgColor::Yellow, b'*'),
+            PowerUpKind::SpeedUp => Tile::new(FgColor::Black, BgColor::Blue, b'>'),
+            PowerUpKind::Reverse => Tile::new(FgColor::Black, BgColor::White, b'?'),
+        }
+    }
+
+    // Which player a power-up affects, when `player` is the one who got it.
+    // `None` means it affects everyone. The other player might be out of a free-for-all, see `Game::opponent`.
+    pub fn target(&self, player: Player) -> Option<Player> {
+        match self {
+            PowerUpKind::Enlarge => Some(player),
+            PowerUpKind::Shrink | PowerUpKind::Reverse => Some(player.other()),
+            PowerUpKind::MultiBall | PowerUpKind::SpeedUp => None,
+        }
+    }
+}
+
+// A power-up waiting on the court to be run into.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub x: usize,
+    pub y: usize,
+}
+
+// A power-up that's been picked up, and is counting down until it wears off.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Effect {
+    pub kind: PowerUpKind,
+    // Who it affects, or `None` if it affects everyone.
+    pub target: Option<Player>,
+    pub timer: u32,
+}
+
+impl Effect {
+    // Whether this effect is a certain kind of power-up, and affects a player.
+    pub fn applies(&self, kind: PowerUpKind, player: Player) -> bool {
+        self.kind == kind && self.target.is_none_or(|target| target == player)
+    }
+}
@@ -0,0 +1,642 @@
+// Online play, where two people play pong from different computers over UDP.
+//
+// Both sides run the whole game and only send each other their inputs, see `rollback` for how
+// neither side has to wait on the other. The host just decides the size of the playfield, the rules,
+// the arena and the seed, so that both games start out the same.
+//
+// If setting up a match fails, the player gets told why and the match ends once they press q,
+// so the input thread and the terminal are always cleaned up before giving up.
+
+pub mod protocol;
+pub mod rollback;
+
+use std::{
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{mpsc::Receiver, Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use console::{Key, Term};
+use session::{spawn_input, EndReason};
+
+use crate::{
+    arena::{Arena, Obstacle},
+    game::{Game, Player},
+    play::{match_over, spawn_renderer, unshare},
+    rules::MatchRules,
+    screen::{BgColor, FgColor, Screen},
+    spectate::Server,
+    stats, ChannelMessage, GameResult, Mode, TICK_TIME,
+};
+use protocol::{Message, MAX_OBSTACLES, MAX_PACKET, PROTOCOL_VERSION};
+use rollback::{Input, Session};
+
+// How many frames local inputs are held back by, unless it's set with `--delay`.
+pub const DEFAULT_INPUT_DELAY: u32 = 2;
+
+// How often each side measures the latency.
+const PING_TIME: Duration = Duration::from_secs(1);
+
+// How long the other side can go without sending anything before they're given up on.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often the guest says hello while waiting for the host to answer.
+const HELLO_TIME: Duration = Duration::from_millis(200);
+
+// How often the host checks whether someone has joined.
+const LOBBY_TIME: Duration = Duration::from_millis(100);
+
+// How an online match ended.
+struct Finish {
+    game: Game,
+    reason: EndReason,
+    // Why the match ended early, if it was down to the other side.
+    dropped: Option<String>,
+    screen: Screen,
+}
+
+// The other side of an online match.
+struct Link {
+    socket: UdpSocket,
+    started: Instant,
+    last_ping: Instant,
+    last_heard: Instant,
+    latency: Option<u64>,
+}
+
+impl Link {
+    // The socket has to be connected to the other side already.
+    fn new(socket: UdpSocket) -> io::Result<Link> {
+        socket.set_read_timeout(None)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Link {
+            socket,
+            started: Instant::now(),
+            last_ping: Instant::now(),
+            last_heard: Instant::now(),
+            latency: None,
+        })
+    }
+
+    // Packets can get lost anyway, so one that couldn't be sent is treated the same way.
+    // If the other side really is gone, the timeout catches it.
+    fn send(&self, message: &Message) {
+        let _ = self.socket.send(&message.encode());
+    }
+
+    // Milliseconds since the link was made, which is what pings carry.
+    fn now(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    // Handles everything that came in since the last call, and gives back the messages about the game.
+    // Pings get answered along the way, and pongs update the latency.
+    // If the other side left or stopped answering, the reason why is given back instead.
+    fn receive(&mut self) -> Result<Vec<Message>, String> {
+        let mut received = Vec::new();
+        let mut packet = [0; MAX_PACKET];
+
+        loop {
+            let length = match self.socket.recv(&mut packet) {
+                Ok(length) => length,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                // This happens when the other side isn't listening yet or anymore, and the timeout covers that.
+                Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => continue,
+                Err(err) => return Err(format!("Lost the connection: {err}.")),
+            };
+
+            self.last_heard = Instant::now();
+
+            // A packet that doesn't make sense is dropped, like one that never arrived.
+            match Message::decode(&packet[..length]) {
+                Ok(Message::Ping { time }) => self.send(&Message::Pong { time }),
+                Ok(Message::Pong { time }) => self.latency = Some(self.now().saturating_sub(time)),
+                Ok(Message::Quit) => return Err(String::from("The other player left.")),
+                Ok(message) => received.push(message),
+                Err(_) => (),
+            }
+        }
+
+        if self.last_heard.elapsed() >= TIMEOUT {
+            return Err(String::from("The other player stopped responding."));
+        }
+
+        if self.last_ping.elapsed() >= PING_TIME {
+            self.last_ping = Instant::now();
+            self.send(&Message::Ping { time: self.now() });
+        }
+
+        Ok(received)
+    }
+
+    // Lets the other side know we're leaving. It's sent a few times in case some get lost.
+    fn leave(&self) {
+        for _ in 0..3 {
+            self.send(&Message::Quit);
+        }
+    }
+}
+
+fn refused(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionRefused, reason)
+}
+
+// Sets up a match, which has to happen the same way on both sides so that both games start out the same.
+pub fn new_game(width: usize, height: usize, rules: MatchRules, seed: u64) -> Game {
+    let mut game = Game::with_size(width, height, rules, seed);
+
+    game.ball_initial_pos();
+
+    game
+}
+
+// Hosts a match on a port, and waits for someone to join it with `connect`.
+// The host plays with paddle 1, and their inputs get held back by `delay` frames.
+// The match is played in `arena` if there is one, and everything gets sent to `spectators` too, if there's a server for them.
+// The stats get added to the `export` file once the match is over, if there is one.
+pub fn host(
+    term: &Term,
+    port: u16,
+    rules: MatchRules,
+    arena: Option<&Arena>,
+    delay: u32,
+    spectators: Option<Server>,
+    export: Option<&str>,
+) -> io::Result<GameResult> {
+    let mut screen = Screen::new(term);
+
+    let seed = rand::random();
+    let mut game = new_game(screen.width, screen.height - 1, rules, seed);
+
+    if let Some(arena) = arena {
+        game.obstacles = arena.obstacles(&game);
+    }
+
+    // The guest gets sent every obstacle in the welcome, so there's a limit to how many there can be.
+    if game.obstacles.len() > MAX_OBSTACLES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the arena has {} obstacles, but only {MAX_OBSTACLES} can be played online",
+                game.obstacles.len()
+            ),
+        ));
+    }
+
+    let socket = UdpSocket::bind(("0.0.0.0", port))?;
+    socket.set_nonblocking(true)?;
+
+    let (keys, input_thread) = spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    if let Some(server) = spectators {
+        screen.share(server);
+    }
+
+    let joined = wait_for_guest(&mut screen, &keys, &socket, port).and_then(|guest| match guest {
+        Some(guest) => {
+            socket.connect(guest)?;
+
+            Link::new(socket).map(Some)
+        }
+        None => Ok(None),
+    });
+
+    let link = match joined {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            input_thread.join().unwrap();
+
+            return Ok(GameResult {
+                score_1: 0,
+                score_2: 0,
+                best: None,
+                reason: EndReason::Quit,
+            });
+        }
+        Err(err) => return Err(give_up(term, &mut screen, &keys, input_thread, err)),
+    };
+
+    let welcome = Message::Welcome {
+        width: game.width as u16,
+        height: game.height as u16,
+        rules,
+        seed,
+        obstacles: game.obstacles.clone(),
+    };
+
+    link.send(&welcome);
+
+    let session = Session::new(game, Player::One, delay);
+    let finish = online_match(screen, session, link, Some(welcome), &keys);
+
+    Ok(wrap_up(finish, &keys, input_thread, export))
+}
+
+// Joins a match someone is hosting at `addr`, like `127.0.0.1:7777`.
+// The guest plays with paddle 2, and their inputs get held back by `delay` frames.
+// Everything gets sent to `spectators` too, if there's a server for them,
+// and the stats get added to the `export` file once the match is over, if there is one.
+pub fn connect(
+    term: &Term,
+    addr: &str,
+    delay: u32,
+    spectators: Option<Server>,
+    export: Option<&str>,
+) -> io::Result<GameResult> {
+    let (keys, input_thread) = spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    let mut screen = Screen::new(term);
+
+    if let Some(server) = spectators {
+        screen.share(server);
+    }
+
+    notice(&mut screen, &format!("Connecting to {addr}..."), "");
+
+    let (game, link) = match join_host(&screen, addr) {
+        Ok(joined) => joined,
+        Err(err) => return Err(give_up(term, &mut screen, &keys, input_thread, err)),
+    };
+
+    let session = Session::new(game, Player::Two, delay);
+    let finish = online_match(screen, session, link, None, &keys);
+
+    Ok(wrap_up(finish, &keys, input_thread, export))
+}
+
+// The guest's side of setting up a match, which gives back the game the host set up and the link to them.
+fn join_host(screen: &Screen, addr: &str) -> io::Result<(Game, Link)> {
+    let socket = open(addr)?;
+    let (width, height, rules, seed, obstacles) = greet_host(&socket)?;
+
+    // The host's playfield is used as is, so it has to fit.
+    if width > screen.width || height > screen.height - 1 {
+        return Err(refused(format!(
+            "the host's playfield is {width}x{height}, which doesn't fit in this terminal"
+        )));
+    }
+
+    let mut game = new_game(width, height, rules, seed);
+    game.obstacles = obstacles;
+
+    Ok((game, Link::new(socket)?))
+}
+
+// Shows why a match couldn't be set up until the player presses q, then stops the input thread
+// and brings the cursor back. The error's given back so that it can be passed on.
+fn give_up(
+    term: &Term,
+    screen: &mut Screen,
+    keys: &Receiver<Key>,
+    input_thread: JoinHandle<()>,
+    err: io::Error,
+) -> io::Error {
+    notice(
+        screen,
+        &format!("Couldn't set up the match: {err}."),
+        "Press q to quit.",
+    );
+
+    for key in keys.iter() {
+        if key == Key::Char('q') {
+            break;
+        }
+    }
+
+    input_thread.join().unwrap();
+    term.show_cursor().unwrap();
+
+    err
+}
+
+// Shows a line of text in the middle of the screen.
+fn notice(screen: &mut Screen, text: &str, status: &str) {
+    screen.reset();
+    screen.text(
+        screen.width.saturating_sub(text.len()) / 2,
+        screen.height / 2,
+        text,
+        FgColor::Default,
+        BgColor::Default,
+    );
+    screen.render(status);
+}
+
+// Waits for someone to say hello, and gives back `None` if the host gives up waiting.
+// Anyone on a different version gets turned away, and the host keeps waiting for someone else.
+fn wait_for_guest(
+    screen: &mut Screen,
+    keys: &Receiver<Key>,
+    socket: &UdpSocket,
+    port: u16,
+) -> io::Result<Option<SocketAddr>> {
+    let mut packet = [0; MAX_PACKET];
+    let mut status = String::from("Press q to quit.");
+
+    loop {
+        if keys.try_iter().any(|key| key == Key::Char('q')) {
+            return Ok(None);
+        }
+
+        match socket.recv_from(&mut packet) {
+            Ok((length, addr)) => match Message::decode(&packet[..length]) {
+                Ok(Message::Hello {
+                    version: PROTOCOL_VERSION,
+                }) => return Ok(Some(addr)),
+                Ok(Message::Hello { version }) => {
+                    let rejected = Message::Rejected {
+                        version: PROTOCOL_VERSION,
+                    };
+
+                    // They'll just time out if this gets lost.
+                    let _ = socket.send_to(&rejected.encode(), addr);
+
+                    status = format!(
+                        "Turned away a player on version {version} of the protocol, this is version {PROTOCOL_VERSION}. Press q to quit."
+                    );
+                }
+                // Anything else is left over from an old match, or not from pong at all.
+                _ => (),
+            },
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => (),
+            Err(err) => return Err(err),
+        }
+
+        notice(
+            screen,
+            &format!("Waiting for a player to join on port {port}..."),
+            &status,
+        );
+
+        thread::sleep(LOBBY_TIME);
+    }
+}
+
+// Makes a socket that only talks to `addr`.
+fn open(addr: &str) -> io::Result<UdpSocket> {
+    let Some(addr) = addr.to_socket_addrs()?.next() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{addr} isn't a valid address"),
+        ));
+    };
+
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local)?;
+
+    socket.connect(addr)?;
+
+    Ok(socket)
+}
+
+// The guest's half of the handshake, which gives back the size of the host's playfield,
+// the rules of the match, the seed and the arena's obstacles.
+fn greet_host(socket: &UdpSocket) -> io::Result<(usize, usize, MatchRules, u64, Vec<Obstacle>)> {
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+    };
+    let started = Instant::now();
+    let mut packet = [0; MAX_PACKET];
+
+    socket.set_read_timeout(Some(HELLO_TIME))?;
+
+    // Either side's packets could get lost, so hello gets said until there's an answer.
+    while started.elapsed() < TIMEOUT {
+        socket.send(&hello.encode())?;
+
+        let length = match socket.recv(&mut packet) {
+            Ok(length) => length,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                continue
+            }
+            Err(err) => return Err(err),
+        };
+
+        match Message::decode(&packet[..length]) {
+            Ok(Message::Welcome {
+                width,
+                height,
+                rules,
+                seed,
+                obstacles,
+            }) => return Ok((width.into(), height.into(), rules, seed, obstacles)),
+            Ok(Message::Rejected { version }) => {
+                return Err(refused(format!(
+                    "the host is on version {version} of the protocol, but this is version {PROTOCOL_VERSION}"
+                )))
+            }
+            // The host might have started sending inputs already if the welcome got lost.
+            _ => (),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the host didn't answer",
+    ))
+}
+
+// Plays a match until it's over or someone leaves.
+// The host passes in its welcome, so it can send it again if the guest never got it.
+fn online_match(
+    screen: Screen,
+    mut session: Session,
+    mut link: Link,
+    welcome: Option<Message>,
+    keys: &Receiver<Key>,
+) -> Finish {
+    let game = Arc::new(Mutex::new(session.game().clone()));
+
+    let (sender, render_thread) = spawn_renderer(screen, Arc::clone(&game), Mode::Online);
+
+    // What the local player has done since the last frame that was played.
+    let mut input = Input::default();
+    let mut note = String::new();
+
+    let (reason, dropped) = loop {
+        thread::sleep(TICK_TIME);
+
+        let mut game = game.lock().unwrap();
+        let mut end = None;
+
+        for key in keys.try_iter() {
+            match key {
+                Key::Char('w') | Key::ArrowUp => input.direction = -1,
+                Key::Char('s') | Key::ArrowDown => input.direction = 1,
+                Key::Char(' ') => input.serve = true,
+                Key::Char('q') => end = Some((EndReason::Quit, None)),
+                _ => (),
+            }
+        }
+
+        if let Err(why) = exchange(&mut session, &mut link, welcome.as_ref()) {
+            end = Some((EndReason::Quit, Some(why)));
+        }
+
+        if let Some(end) = end {
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break end;
+        }
+
+        // If the game's too far ahead of the other player it waits for them,
+        // and whatever the local player did carries over to the next frame.
+        if session.can_advance() {
+            session.advance(input);
+            input = Input::default();
+        }
+
+        send_inputs(&mut session, &link);
+
+        let mut new_note = match link.latency {
+            Some(latency) => format!("Ping: {latency} ms"),
+            None => String::new(),
+        };
+
+        if session.desynced {
+            new_note.push_str(" (out of sync!)");
+        }
+
+        if new_note != note {
+            note = new_note;
+            sender.send(ChannelMessage::Note(note.clone())).unwrap();
+        }
+
+        // A winner only counts once it can't be undone by going back.
+        if session.confirmed_game().winner.is_some() {
+            *game = session.confirmed_game().clone();
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break (EndReason::GameOver, None);
+        }
+
+        *game = session.game().clone();
+    };
+
+    let screen = render_thread.join().unwrap();
+
+    if reason == EndReason::Quit && dropped.is_none() {
+        link.leave();
+    }
+
+    // The other side might still need the last few inputs to see the match end the same way,
+    // so they keep getting sent until they've all arrived or the other side goes quiet.
+    if reason == EndReason::GameOver {
+        while !session.all_acked() && exchange(&mut session, &mut link, welcome.as_ref()).is_ok() {
+            send_inputs(&mut session, &link);
+            thread::sleep(TICK_TIME);
+        }
+    }
+
+    Finish {
+        game: unshare(game),
+        reason,
+        dropped,
+        screen,
+    }
+}
+
+// Handles everything the other side sent since the last call.
+fn exchange(
+    session: &mut Session,
+    link: &mut Link,
+    welcome: Option<&Message>,
+) -> Result<(), String> {
+    for message in link.receive()? {
+        match message {
+            Message::Inputs { ack, start, inputs } => {
+                session.ack(ack);
+                session.add_remote_inputs(start, &inputs);
+            }
+            Message::Checksum { frame, checksum } => session.add_remote_checksum(frame, checksum),
+            Message::Hello { .. } => {
+                if let Some(welcome) = welcome {
+                    link.send(welcome);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+// Sends the other side every input they haven't got yet, along with any new checksums.
+fn send_inputs(session: &mut Session, link: &Link) {
+    let (start, inputs) = session.unacked_inputs();
+
+    link.send(&Message::Inputs {
+        ack: session.remote_frames(),
+        start,
+        inputs: inputs.to_vec(),
+    });
+
+    for (frame, checksum) in session.take_checksums() {
+        link.send(&Message::Checksum { frame, checksum });
+    }
+}
+
+// Shows how the match went, unless the player quit it themselves, and waits for the input thread to finish.
+// Matches that were played to the end get their stats added to the `export` file, if there is one.
+fn wrap_up(
+    finish: Finish,
+    keys: &Receiver<Key>,
+    input_thread: JoinHandle<()>,
+    export: Option<&str>,
+) -> GameResult {
+    let Finish {
+        game,
+        reason,
+        dropped,
+        mut screen,
+    } = finish;
+
+    // The input thread has already stopped if the player quit themselves.
+    if reason == EndReason::GameOver || dropped.is_some() {
+        let saved = match export {
+            Some(path) if reason == EndReason::GameOver => stats::export(path, &game, Mode::Online),
+            _ => Ok(()),
+        };
+
+        let status = match (dropped, saved) {
+            (Some(why), _) => format!("{why} Press q to quit."),
+            (None, Err(err)) => format!("Couldn't save the stats: {err}. Press q to quit."),
+            (None, Ok(())) => String::from("Press q to quit."),
+        };
+
+        match_over(&mut screen, &game, Mode::Online, &status);
+
+        for key in keys.iter() {
+            if key == Key::Char('q') {
+                break;
+            }
+        }
+    }
+
+    input_thread.join().unwrap();
+
+    GameResult {
+        score_1: game.points_1,
+        score_2: game.points_2,
+        best: None,
+        reason,
+    }
+}
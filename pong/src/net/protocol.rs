@@ -0,0 +1,278 @@
+use std::io::{self, Read};
+
+use super::rollback::Input;
+use crate::{
+    arena::Obstacle,
+    rules::{MatchRules, ServeRule},
+};
+
+// Every handshake starts with this, so anything that isn't pong gets turned away straight away.
+const MAGIC: [u8; 4] = *b"PONG";
+
+// Bumped whenever the messages change, so mismatched versions don't try to play together.
+pub const PROTOCOL_VERSION: u16 = 6;
+
+// The most inputs that fit in one message.
+pub const MAX_INPUTS: usize = u8::MAX as usize;
+
+// The biggest packet either side sends or reads.
+pub const MAX_PACKET: usize = 512;
+
+// How big a welcome is before its obstacles: the kind, the playfield, the rules, the seed and how many obstacles there are.
+pub const WELCOME_HEADER: usize = 1 + 2 * 2 + 4 * 4 + 1 + 1 + 4 + 8 + 1;
+
+// How many bytes every obstacle takes up in a welcome.
+pub const OBSTACLE_SIZE: usize = 4 * 2;
+
+// The most obstacles an arena can have to be played online, so the welcome still fits in one packet.
+pub const MAX_OBSTACLES: usize = (MAX_PACKET - WELCOME_HEADER) / OBSTACLE_SIZE;
+
+// The first byte of every message, saying which kind it is.
+const HELLO: u8 = 0;
+const WELCOME: u8 = 1;
+const REJECTED: u8 = 2;
+const INPUTS: u8 = 3;
+const CHECKSUM: u8 = 4;
+const PING: u8 = 5;
+const PONG: u8 = 6;
+const QUIT: u8 = 7;
+
+// Everything the host and the guest send to each other, one message per packet.
+// Numbers are sent big endian.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Message {
+    // Sent by the guest to start the handshake, over and over until the host answers.
+    Hello {
+        version: u16,
+    },
+
+    // The host accepting the guest, along with everything needed to set up the same game on both sides.
+    Welcome {
+        width: u16,
+        height: u16,
+        rules: MatchRules,
+        seed: u64,
+        // The arena's obstacles, already fitted to the playfield.
+        obstacles: Vec<Obstacle>,
+    },
+
+    // The host turning the guest away, because they don't speak the same version.
+    Rejected {
+        version: u16,
+    },
+
+    // How far a player moved their paddle on every frame from `start` on.
+    // Packets can get lost, so every input the other side hasn't acknowledged yet gets sent again each time.
+    // `ack` is how many frames of the other side's inputs have arrived so far.
+    Inputs {
+        ack: u32,
+        start: u32,
+        inputs: Vec<Input>,
+    },
+
+    // A checksum of the game after a frame, so both sides can tell if their games ever stop matching.
+    Checksum {
+        frame: u32,
+        checksum: u64,
+    },
+
+    // Used to measure latency, a ping gets answered with a pong holding the same time.
+    Ping {
+        time: u64,
+    },
+    Pong {
+        time: u64,
+    },
+
+    // The other side is leaving.
+    Quit,
+}
+
+impl Message {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            Message::Hello { version } => {
+                bytes.push(HELLO);
+                bytes.extend_from_slice(&MAGIC);
+                bytes.extend_from_slice(&version.to_be_bytes());
+            }
+            Message::Welcome {
+                width,
+                height,
+                rules,
+                seed,
+                obstacles,
+            } => {
+                bytes.push(WELCOME);
+                bytes.extend_from_slice(&width.to_be_bytes());
+                bytes.extend_from_slice(&height.to_be_bytes());
+                bytes.extend_from_slice(&rules.points_to_win.to_be_bytes());
+                bytes.extend_from_slice(&rules.win_by.to_be_bytes());
+                bytes.extend_from_slice(&rules.best_of.to_be_bytes());
+                bytes.extend_from_slice(&rules.serve_delay.to_be_bytes());
+                bytes.push(rules.serve as u8);
+                bytes.push(rules.power_ups as u8);
+                bytes.extend_from_slice(&rules.chaos.to_be_bytes());
+                bytes.extend_from_slice(&seed.to_be_bytes());
+                bytes.push(obstacles.len() as u8);
+
+                for obstacle in obstacles {
+                    for number in [obstacle.x, obstacle.y, obstacle.width, obstacle.height] {
+                        bytes.extend_from_slice(&(number as u16).to_be_bytes());
+                    }
+                }
+            }
+            Message::Rejected { version } => {
+                bytes.push(REJECTED);
+                bytes.extend_from_slice(&version.to_be_bytes());
+            }
+            Message::Inputs { ack, start, inputs } => {
+                bytes.push(INPUTS);
+                bytes.extend_from_slice(&ack.to_be_bytes());
+                bytes.extend_from_slice(&start.to_be_bytes());
+                bytes.push(inputs.len() as u8);
+                bytes.extend(inputs.iter().map(|input| input.to_byte()));
+            }
+            Message::Checksum { frame, checksum } => {
+                bytes.push(CHECKSUM);
+                bytes.extend_from_slice(&frame.to_be_bytes());
+                bytes.extend_from_slice(&checksum.to_be_bytes());
+            }
+            Message::Ping { time } => {
+                bytes.push(PING);
+                bytes.extend_from_slice(&time.to_be_bytes());
+            }
+            Message::Pong { time } => {
+                bytes.push(PONG);
+                bytes.extend_from_slice(&time.to_be_bytes());
+            }
+            Message::Quit => bytes.push(QUIT),
+        }
+
+        bytes
+    }
+
+    // Reads a message out of a packet.
+    pub fn decode(mut bytes: &[u8]) -> io::Result<Message> {
+        Message::read(&mut bytes)
+    }
+
+    // Blocks until a whole message has been read.
+    pub fn read(reader: &mut impl Read) -> io::Result<Message> {
+        let message = match read_u8(reader)? {
+            HELLO => {
+                let mut magic = [0; 4];
+                reader.read_exact(&mut magic)?;
+
+                if magic != MAGIC {
+                    return Err(invalid("the other side isn't playing pong"));
+                }
+
+                Message::Hello {
+                    version: read_u16(reader)?,
+                }
+            }
+            WELCOME => {
+                let width = read_u16(reader)?;
+                let height = read_u16(reader)?;
+                let rules = MatchRules {
+                    points_to_win: read_u32(reader)?,
+                    win_by: read_u32(reader)?,
+                    best_of: read_u32(reader)?,
+                    serve_delay: read_u32(reader)?,
+                    serve: match read_u8(reader)? {
+                        0 => ServeRule::Alternate,
+                        _ => ServeRule::Loser,
+                    },
+                    power_ups: read_u8(reader)? != 0,
+                    chaos: read_u32(reader)?,
+                    // Free-for-alls aren't played online, so lives don't get sent.
+                    ..MatchRules::default()
+                };
+                let seed = read_u64(reader)?;
+                let mut obstacles = Vec::new();
+
+                for _ in 0..read_u8(reader)? {
+                    obstacles.push(Obstacle {
+                        x: read_u16(reader)? as usize,
+                        y: read_u16(reader)? as usize,
+                        width: read_u16(reader)? as usize,
+                        height: read_u16(reader)? as usize,
+                    });
+                }
+
+                Message::Welcome {
+                    width,
+                    height,
+                    rules,
+                    seed,
+                    obstacles,
+                }
+            }
+            REJECTED => Message::Rejected {
+                version: read_u16(reader)?,
+            },
+            INPUTS => {
+                let ack = read_u32(reader)?;
+                let start = read_u32(reader)?;
+                let mut inputs = vec![0; read_u8(reader)? as usize];
+
+                reader.read_exact(&mut inputs)?;
+
+                Message::Inputs {
+                    ack,
+                    start,
+                    inputs: inputs.into_iter().map(Input::from_byte).collect(),
+                }
+            }
+            CHECKSUM => Message::Checksum {
+                frame: read_u32(reader)?,
+                checksum: read_u64(reader)?,
+            },
+            PING => Message::Ping {
+                time: read_u64(reader)?,
+            },
+            PONG => Message::Pong {
+                time: read_u64(reader)?,
+            },
+            QUIT => Message::Quit,
+            kind => return Err(invalid(&format!("unknown message kind {kind}"))),
+        };
+
+        Ok(message)
+    }
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(bytes[0])
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u16::from_be_bytes(bytes))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u64::from_be_bytes(bytes))
+}
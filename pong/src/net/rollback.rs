@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::game::{Game, Player};
+
+use super::protocol::MAX_INPUTS;
+
+// How many frames the game can run ahead of the other player's inputs before it waits for them.
+pub const MAX_ROLLBACK: u32 = 8;
+
+// How often the game gets checksummed, in frames.
+const CHECKSUM_INTERVAL: u32 = 30;
+
+// What a player did during a frame.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Input {
+    // Which way they pushed their paddle, -1, 0 or 1.
+    pub direction: i8,
+    pub serve: bool,
+}
+
+// The bits of an input once it's packed into a byte to send.
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const SERVE: u8 = 4;
+
+impl Input {
+    pub fn to_byte(self) -> u8 {
+        let direction = match self.direction.signum() {
+            -1 => UP,
+            1 => DOWN,
+            _ => 0,
+        };
+
+        direction | if self.serve { SERVE } else { 0 }
+    }
+
+    pub fn from_byte(byte: u8) -> Input {
+        let direction = match (byte & UP != 0, byte & DOWN != 0) {
+            (true, false) => -1,
+            (false, true) => 1,
+            _ => 0,
+        };
+
+        Input {
+            direction,
+            serve: byte & SERVE != 0,
+        }
+    }
+}
+
+// Runs one side of an online match without waiting on the other player.
+//
+// Both sides run the whole game. Whenever the other player's input for a frame hasn't arrived yet,
+// it's guessed to be the same as their last one, and the game carries on with the guess.
+// A copy of the game is saved before every frame that was guessed, so once the real input turns up,
+// the game can go back to that frame and play it again if the guess was wrong.
+//
+// Local inputs can also be held back by a few frames, which gives them time to reach the other side
+// before they're needed and so means less going back.
+pub struct Session {
+    game: Game,
+    local: Player,
+
+    // How many frames have been played.
+    frame: u32,
+
+    // Every frame before this was played with both players' real inputs, so it won't change anymore.
+    confirmed: u32,
+
+    // A copy of the game from before every frame since `confirmed`, oldest first.
+    saved: VecDeque<Game>,
+
+    // The inputs each player made, by frame. The remote inputs only go as far as have arrived.
+    local_inputs: Vec<Input>,
+    remote_inputs: Vec<Input>,
+
+    // What the remote inputs were guessed to be, for every frame since `confirmed`.
+    guesses: VecDeque<Input>,
+
+    // How many local inputs the other side has said they've got.
+    acked: u32,
+
+    // Checksums that haven't been matched up with the other side's yet, by frame.
+    local_checksums: BTreeMap<u32, u64>,
+    remote_checksums: BTreeMap<u32, u64>,
+    unsent_checksums: Vec<(u32, u64)>,
+
+    pub rollbacks: u32,
+    pub desynced: bool,
+}
+
+impl Session {
+    // Starts a session with `game` as frame 0, where `local` is the paddle this side controls, either player one or two.
+    // Local inputs are played `delay` frames after they're added.
+    pub fn new(game: Game, local: Player, delay: u32) -> Session {
+        Session {
+            game,
+            local,
+            frame: 0,
+            confirmed: 0,
+            saved: VecDeque::new(),
+            local_inputs: vec![Input::default(); delay as usize],
+            remote_inputs: Vec::new(),
+            guesses: VecDeque::new(),
+            acked: 0,
+            local_checksums: BTreeMap::new(),
+            remote_checksums: BTreeMap::new(),
+            unsent_checksums: Vec::new(),
+            rollbacks: 0,
+            desynced: false,
+        }
+    }
+
+    // The game as it is right now, which might still change if any guesses turn out wrong.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // The game as of the last frame that can't change anymore.
+    pub fn confirmed_game(&self) -> &Game {
+        self.saved.front().unwrap_or(&self.game)
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn confirmed_frame(&self) -> u32 {
+        self.confirmed
+    }
+
+    // Whether the next frame can be played now, which it can't if the game is already too far ahead of the other player.
+    pub fn can_advance(&self) -> bool {
+        self.frame - self.confirmed < MAX_ROLLBACK
+    }
+
+    // Plays the next frame, with `input` being what the local player did since the last one.
+    // The input only gets played after the delay, and the remote input is guessed if it hasn't arrived yet.
+    pub fn advance(&mut self, input: Input) {
+        self.local_inputs.push(input);
+        self.play_frame();
+    }
+
+    fn play_frame(&mut self) {
+        let local = self.local_inputs[self.frame as usize];
+        let remote = self.remote_input(self.frame);
+
+        self.saved.push_back(self.game.clone());
+        self.guesses.push_back(remote);
+
+        let (input_1, input_2) = match self.local {
+            Player::One => (local, remote),
+            _ => (remote, local),
+        };
+
+        for (player, input) in [(Player::One, input_1), (Player::Two, input_2)] {
+            self.game.push_paddle(player, input.direction);
+
+            if input.serve {
+                self.game.serve(player);
+            }
+        }
+
+        self.game.tick();
+        self.frame += 1;
+    }
+
+    // The remote input for a frame, or a guess at it if it hasn't arrived yet.
+    fn remote_input(&self, frame: u32) -> Input {
+        match self.remote_inputs.get(frame as usize) {
+            Some(input) => *input,
+            // People tend to keep doing what they were doing, so the guess is their last input.
+            // Serving only happens once though, so that's never guessed.
+            None => Input {
+                serve: false,
+                ..self.remote_inputs.last().copied().unwrap_or_default()
+            },
+        }
+    }
+
+    // Adds inputs from the other side, starting at frame `start`.
+    // Anything that was already known gets skipped, and anything after a gap has to wait until it's sent again.
+    // If any guesses were wrong, the game goes back to the first wrong one and plays on from there.
+    pub fn add_remote_inputs(&mut self, start: u32, inputs: &[Input]) {
+        for (frame, input) in (start..).zip(inputs) {
+            if frame as usize == self.remote_inputs.len() {
+                self.remote_inputs.push(*input);
+            }
+        }
+
+        let known = (self.remote_inputs.len() as u32).min(self.frame);
+        let wrong = (self.confirmed..known).find(|frame| {
+            self.guesses[(frame - self.confirmed) as usize] != self.remote_inputs[*frame as usize]
+        });
+
+        if let Some(wrong) = wrong {
+            let end = self.frame;
+            let index = (wrong - self.confirmed) as usize;
+
+            self.game = self.saved[index].clone();
+            self.saved.truncate(index);
+            self.guesses.truncate(index);
+            self.frame = wrong;
+            self.rollbacks += 1;
+
+            while self.frame < end {
+                self.play_frame();
+            }
+        }
+
+        // Everything up to the last remote input that's arrived is now set in stone.
+        while self.confirmed < known {
+            let game = self.saved.pop_front().unwrap();
+
+            self.guesses.pop_front();
+
+            if self.confirmed.is_multiple_of(CHECKSUM_INTERVAL) {
+                self.add_checksum(self.confirmed, game.checksum());
+            }
+
+            self.confirmed += 1;
+        }
+    }
+
+    // How many frames of remote inputs have arrived, which is what gets sent back as the acknowledgement.
+    pub fn remote_frames(&self) -> u32 {
+        self.remote_inputs.len() as u32
+    }
+
+    pub fn ack(&mut self, ack: u32) {
+        self.acked = self.acked.max(ack).min(self.local_inputs.len() as u32);
+    }
+
+    // Whether the other side has got every local input.
+    pub fn all_acked(&self) -> bool {
+        self.acked as usize == self.local_inputs.len()
+    }
+
+    // The local inputs the other side hasn't acknowledged yet, and the frame they start at.
+    pub fn unacked_inputs(&self) -> (u32, &[Input]) {
+        let start = self.acked as usize;
+        let end = self.local_inputs.len().min(start + MAX_INPUTS);
+
+        (self.acked, &self.local_inputs[start..end])
+    }
+
+    // Takes the checksums that still need sending to the other side.
+    pub fn take_checksums(&mut self) -> Vec<(u32, u64)> {
+        std::mem::take(&mut self.unsent_checksums)
+    }
+
+    fn add_checksum(&mut self, frame: u32, checksum: u64) {
+        self.unsent_checksums.push((frame, checksum));
+
+        match self.remote_checksums.remove(&frame) {
+            Some(remote) => self.desynced |= remote != checksum,
+            None => {
+                self.local_checksums.insert(frame, checksum);
+            }
+        }
+    }
+
+    // Checks a checksum from the other side against ours for the same frame.
+    pub fn add_remote_checksum(&mut self, frame: u32, checksum: u64) {
+        match self.local_checksums.remove(&frame) {
+            Some(local) => self.desynced |= local != checksum,
+            None => {
+                self.remote_checksums.insert(frame, checksum);
+            }
+        }
+    }
+}
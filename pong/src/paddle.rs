@@ -0,0 +1,154 @@
+use std::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use crate::TICK_TIME;
+
+// How much faster a paddle gets every tick its key is held, in rows per tick.
+pub const ACCELERATION: f32 = 0.4;
+
+// How much a paddle slows down every tick once its key is let go.
+pub const FRICTION: f32 = 0.6;
+
+// The fastest a paddle can go, in rows per tick.
+pub const MAX_SPEED: f32 = 1.6;
+
+// Terminals don't say when a key is let go, only send it again every so often while it's held.
+// After the first press there's a pause before it starts repeating, which is usually somewhere up to half a second,
+// and after that it repeats a lot faster than the game ticks.
+pub const REPEAT_DELAY: Duration = Duration::from_millis(500);
+
+// How many ticks a key counts as held after it was first pressed, which bridges the pause before it repeats.
+// The paddle just keeps going the way it was in the meantime, so tapping a key doesn't send it flying.
+pub const FIRST_HOLD_TICKS: u8 = (REPEAT_DELAY.as_millis() / TICK_TIME.as_millis()) as u8 + 1;
+
+// How many ticks a key counts as held after it was last pressed, once it's repeating.
+pub const HOLD_TICKS: u8 = 2;
+
+// A player's paddle, which speeds up while its key is held and slides to a stop once it's let go.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Paddle {
+    // Where the middle of the paddle is, which is a row for the left and right paddles, and a column for the others.
+    pub position: f32,
+
+    // How far the paddle moves every tick, which also puts spin on the ball.
+    pub velocity: f32,
+
+    // Which way the paddle's being pushed, and for how many more ticks.
+    direction: i8,
+    held: u8,
+
+    // Whether the key has been pressed again since it was first pressed, so it's definitely being held down.
+    repeating: bool,
+}
+
+impl Paddle {
+    pub fn new(position: f32) -> Paddle {
+        Paddle {
+            position,
+            velocity: 0.0,
+            direction: 0,
+            held: 0,
+            repeating: false,
+        }
+    }
+
+    // Pushes the paddle one way, like holding down its key.
+    // Pushing against the way it's already going stops it straight away, so changing direction doesn't feel sluggish.
+    pub fn push(&mut self, direction: i8) {
+        let direction = direction.signum();
+
+        if direction == 0 {
+            return;
+        }
+
+        if self.velocity * (direction as f32) < 0.0 {
+            self.velocity = 0.0;
+        }
+
+        // Another press the same way while it's still held can only be the key repeating.
+        if self.held > 0 && self.direction == direction {
+            self.repeating = true;
+            self.held = self.held.max(HOLD_TICKS);
+        } else {
+            self.repeating = false;
+            self.held = FIRST_HOLD_TICKS;
+        }
+
+        self.direction = direction;
+    }
+
+    // Pushes the paddle one way for the next tick only, like a key that's already repeating.
+    // The CPU moves its paddle like this, since it decides every tick whether to keep going and never waits on key repeats.
+    pub fn nudge(&mut self, direction: i8) {
+        let direction = direction.signum();
+
+        if direction == 0 {
+            return;
+        }
+
+        if self.velocity * (direction as f32) < 0.0 {
+            self.velocity = 0.0;
+        }
+
+        self.direction = direction;
+        self.held = 1;
+        self.repeating = true;
+    }
+
+    // Moves the paddle along for a tick, keeping its middle between `min` and `max`.
+    // Everything is multiplied by `scale`, for paddles that move over tiles of a different size.
+    pub fn tick(&mut self, min: f32, max: f32, scale: f32) {
+        if self.held > 0 {
+            // Only the first press speeds the paddle up until the key starts repeating.
+            let pressed = self.repeating || self.held == FIRST_HOLD_TICKS;
+
+            self.held -= 1;
+
+            if pressed {
+                self.velocity = (self.velocity + ACCELERATION * scale * self.direction as f32)
+                    .clamp(-MAX_SPEED * scale, MAX_SPEED * scale);
+            }
+        } else if self.velocity.abs() <= FRICTION * scale {
+            self.velocity = 0.0;
+        } else {
+            self.velocity -= FRICTION * scale * self.velocity.signum();
+        }
+
+        self.position += self.velocity;
+
+        // Running into the edge stops the paddle dead, instead of leaving it pushing against it.
+        let clamped = self.position.clamp(min, max.max(min));
+
+        if clamped != self.position {
+            self.position = clamped;
+            self.velocity = 0.0;
+        }
+    }
+
+    // The tile the middle of the paddle is drawn on.
+    pub fn tile(&self) -> usize {
+        self.position.round() as usize
+    }
+
+    // Where the paddle would end up if it was left alone from now on, ignoring the edges of the court.
+    pub fn resting_position(&self, scale: f32) -> f32 {
+        let mut paddle = *self;
+
+        while paddle.held > 0 || paddle.velocity != 0.0 {
+            paddle.tick(f32::NEG_INFINITY, f32::INFINITY, scale);
+        }
+
+        paddle.position
+    }
+}
+
+// Paddles get hashed as part of `Game::checksum`, which only cares whether the numbers are exactly the same.
+impl Hash for Paddle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position.to_bits().hash(state);
+        self.velocity.to_bits().hash(state);
+        (self.direction, self.held, self.repeating).hash(state);
+    }
+}
@@ -0,0 +1,592 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use console::{Key, Term};
+use session::EndReason;
+
+use crate::{
+    ai::{Cpu, Difficulty},
+    arena::Arena,
+    game::{Game, Player, TickStatus, PLAYERS},
+    menu::{MenuOption, DEFAULT_MENU, DEFAULT_SEATS, SEAT_NAMES},
+    modifiers::PowerUpKind,
+    rules::MatchRules,
+    screen::{BgColor, FgColor, Screen, Tile},
+    spectate::Server,
+    stats, ChannelMessage, GameResult, Mode, Seat, PADDLE_PADDING, RENDER_TIME, TICK_TIME,
+};
+
+// Keeps playing matches until the player quits, and returns the result of the last one, along with the best score.
+// This is the entry point for anything that wants to host pong, like a launcher.
+// Every match is played in `arena` if there is one, and everything gets sent to `spectators` too, if there's a server for them.
+// The stats for every finished match get added to the `export` file, if there is one.
+// Every match plays out from `seed` if it's given, like for a daily challenge, otherwise they're all random.
+pub fn run(
+    term: &Term,
+    rules: MatchRules,
+    arena: Option<&Arena>,
+    spectators: Option<Server>,
+    export: Option<&str>,
+    seed: Option<u64>,
+) -> GameResult {
+    let (keys, input_thread) = session::spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    let mut screen = Screen::new(term);
+
+    if let Some(server) = spectators {
+        screen.share(server);
+    }
+
+    let Some(mode) = choose_mode(&mut screen, &keys) else {
+        input_thread.join().unwrap();
+
+        return GameResult {
+            score_1: 0,
+            score_2: 0,
+            best: None,
+            reason: EndReason::Quit,
+        };
+    };
+
+    let mut best = None;
+
+    let result = loop {
+        let (game, reason, returned_screen) = play(screen, mode, rules, arena, seed, &keys);
+        screen = returned_screen;
+
+        if let Mode::Cpu(_) = mode {
+            best = best.max(Some(game.points_1));
+        }
+
+        let result = GameResult {
+            score_1: game.points_1,
+            score_2: game.points_2,
+            best,
+            reason,
+        };
+
+        if reason == EndReason::Quit {
+            break result;
+        }
+
+        let status = match export.map(|path| stats::export(path, &game, mode)) {
+            Some(Err(err)) => {
+                format!("Couldn't save the stats: {err}. Press r to play again, or q to quit.")
+            }
+            _ => String::from("Press r to play again, or q to quit."),
+        };
+
+        match_over(&mut screen, &game, mode, &status);
+
+        if !session::play_again(&keys) {
+            break result;
+        }
+    };
+
+    input_thread.join().unwrap();
+
+    result
+}
+
+// Shows the start menu until the player picks how they want to play, or quits.
+fn choose_mode(screen: &mut Screen, keys: &Receiver<Key>) -> Option<Mode> {
+    let mut menu = DEFAULT_MENU;
+
+    loop {
+        let title = "Pong";
+        let left = screen.width.saturating_sub(title.len()) / 2;
+        let top = (screen.height / 2).saturating_sub(menu.options.len() / 2 + 2);
+
+        screen.reset();
+        screen.text(left, top, title, FgColor::Default, BgColor::Default);
+
+        let left = screen.width.saturating_sub(20) / 2;
+
+        for (i, option) in menu.options.iter().enumerate() {
+            screen.text(
+                left,
+                top + 2 + i,
+                option.to_str(),
+                FgColor::Default,
+                BgColor::Default,
+            );
+
+            if i == menu.cursor_index {
+                screen.text(
+                    left.saturating_sub(2),
+                    top + 2 + i,
+                    ">",
+                    FgColor::Default,
+                    BgColor::Default,
+                );
+            }
+        }
+
+        screen.render("Use the arrow keys and enter to pick a mode, or q to quit.");
+
+        match keys.recv() {
+            Ok(Key::ArrowUp) => menu.up(),
+            Ok(Key::ArrowDown) => menu.down(),
+            Ok(Key::Enter) => match menu.selected() {
+                MenuOption::Play(Mode::FreeForAll(_)) => {
+                    if let Some(seats) = choose_seats(screen, keys)? {
+                        return Some(Mode::FreeForAll(seats));
+                    }
+                }
+                MenuOption::Play(mode) => return Some(mode),
+            },
+            Ok(Key::Char('q')) | Err(_) => return None,
+            _ => (),
+        }
+    }
+}
+
+// Lets the players pick who sits on each side of the court before a free-for-all.
+// Gives back `None` if they quit, and `Some(None)` if they go back to the start menu.
+fn choose_seats(screen: &mut Screen, keys: &Receiver<Key>) -> Option<Option<[Seat; 4]>> {
+    let mut menu = DEFAULT_SEATS;
+    let mut status =
+        "Use up and down to pick a seat, left and right to change it, and enter to start.";
+
+    loop {
+        let title = "Who's playing?";
+        let left = screen.width.saturating_sub(title.len()) / 2;
+        let top = (screen.height / 2).saturating_sub(menu.seats.len() / 2 + 2);
+
+        screen.reset();
+        screen.text(left, top, title, FgColor::Default, BgColor::Default);
+
+        let left = screen.width.saturating_sub(26) / 2;
+
+        for (i, seat) in menu.seats.iter().enumerate() {
+            screen.text(
+                left,
+                top + 2 + i,
+                &format!("{:<16}{}", SEAT_NAMES[i], seat.to_str()),
+                FgColor::Default,
+                BgColor::Default,
+            );
+
+            if i == menu.cursor_index {
+                screen.text(
+                    left.saturating_sub(2),
+                    top + 2 + i,
+                    ">",
+                    FgColor::Default,
+                    BgColor::Default,
+                );
+            }
+        }
+
+        screen.render(status);
+
+        match keys.recv() {
+            Ok(Key::ArrowUp) => menu.up(),
+            Ok(Key::ArrowDown) => menu.down(),
+            Ok(Key::ArrowLeft | Key::ArrowRight) => menu.change(),
+            Ok(Key::Enter) if menu.ready() => return Some(Some(menu.seats)),
+            Ok(Key::Enter) => {
+                status = "At least two seats need someone in them. Press esc to go back."
+            }
+            Ok(Key::Escape) => return Some(None),
+            Ok(Key::Char('q')) | Err(_) => return None,
+            _ => (),
+        }
+    }
+}
+
+// Draws a summary of a finished match.
+pub(crate) fn match_over(screen: &mut Screen, game: &Game, mode: Mode, status: &str) {
+    let winner = match game.winner {
+        Some(player) => format!("{} wins the match!", mode.player_name(player)),
+        None => String::from("Match over!"),
+    };
+
+    let mut lines = vec![winner, String::new()];
+
+    match game.lives {
+        Some(lives) => {
+            for player in PLAYERS {
+                if let Mode::FreeForAll(seats) = mode {
+                    if seats[player.index()] == Seat::Empty {
+                        continue;
+                    }
+                }
+
+                lines.push(match lives[player.index()] {
+                    0 => format!("{}: knocked out", mode.player_name(player)),
+                    left => format!("{}: {left} lives left", mode.player_name(player)),
+                });
+            }
+        }
+        None => {
+            lines.push(format!("Games: {} - {}", game.games_1, game.games_2));
+            lines.push(format!("Points: {} - {}", game.points_1, game.points_2));
+        }
+    }
+
+    lines.push(String::new());
+    lines.extend(stats::summary(game, mode));
+
+    let top = screen.height.saturating_sub(lines.len()) / 2;
+
+    screen.reset();
+
+    for (i, line) in lines.iter().enumerate() {
+        screen.text(
+            screen.width.saturating_sub(line.len()) / 2,
+            top + i,
+            line,
+            FgColor::Default,
+            BgColor::Default,
+        );
+    }
+
+    screen.render(status);
+}
+
+// Spreads three pieces of text across a line, on the left, in the middle, and on the right.
+fn spread(width: usize, left: &str, middle: &str, right: &str) -> String {
+    let middle_start = width.saturating_sub(middle.len()) / 2;
+    let right_start = width.saturating_sub(right.len());
+
+    format!(
+        "{left}{}{middle}{}{right}",
+        " ".repeat(middle_start.saturating_sub(left.len())),
+        " ".repeat(right_start.saturating_sub(middle_start + middle.len())),
+    )
+}
+
+// Plays a single match until it's either over or the player quits.
+// The finished game and the screen are handed back afterwards.
+fn play(
+    screen: Screen,
+    mode: Mode,
+    rules: MatchRules,
+    arena: Option<&Arena>,
+    seed: Option<u64>,
+    keys: &Receiver<Key>,
+) -> (Game, EndReason, Screen) {
+    let mut game = Game::new(&screen, rules, seed.unwrap_or_else(rand::random));
+
+    if let Mode::FreeForAll(seats) = mode {
+        let players: Vec<Player> = PLAYERS
+            .into_iter()
+            .filter(|player| seats[player.index()] != Seat::Empty)
+            .collect();
+
+        game.free_for_all(&players);
+    }
+
+    if let Some(arena) = arena {
+        game.obstacles = arena.obstacles(&game);
+    }
+
+    game.ball_initial_pos();
+
+    let mut cpus: Vec<Cpu> = match mode {
+        Mode::Cpu(difficulty) => vec![Cpu::new(difficulty, Player::Two, &game)],
+        // Every CPU in a free-for-all plays on medium.
+        Mode::FreeForAll(seats) => PLAYERS
+            .into_iter()
+            .filter(|player| seats[player.index()] == Seat::Cpu)
+            .map(|player| Cpu::new(Difficulty::Medium, player, &game))
+            .collect(),
+        Mode::TwoPlayer | Mode::Online => Vec::new(),
+    };
+
+    // Which paddles get moved from the keyboard, in `Player::index` order.
+    let humans = match mode {
+        Mode::FreeForAll(seats) => seats.map(|seat| seat == Seat::Human),
+        _ => [true, cpus.is_empty(), false, false],
+    };
+    let alone = matches!(mode, Mode::Cpu(_));
+
+    let game = Arc::new(Mutex::new(game));
+
+    let (sender, render_thread) = spawn_renderer(screen, Arc::clone(&game), mode);
+
+    // The game loop, it handles any input that came in and then ticks every 60 ms.
+    let reason = loop {
+        thread::sleep(TICK_TIME);
+
+        let mut game = game.lock().unwrap();
+        let mut reason = None;
+
+        for key in keys.try_iter() {
+            match key {
+                Key::Char('w') if humans[0] => game.push_paddle(Player::One, -1),
+                Key::Char('s') if humans[0] => game.push_paddle(Player::One, 1),
+                // Player 1 can use the arrow keys too when they're playing alone.
+                Key::ArrowUp if alone => game.push_paddle(Player::One, -1),
+                Key::ArrowDown if alone => game.push_paddle(Player::One, 1),
+                Key::ArrowUp if humans[1] => game.push_paddle(Player::Two, -1),
+                Key::ArrowDown if humans[1] => game.push_paddle(Player::Two, 1),
+                Key::Char('z') if humans[2] => game.push_paddle(Player::Three, -1),
+                Key::Char('x') if humans[2] => game.push_paddle(Player::Three, 1),
+                Key::Char('n') if humans[3] => game.push_paddle(Player::Four, -1),
+                Key::Char('m') if humans[3] => game.push_paddle(Player::Four, 1),
+                // Everyone serves with the key next to their movement keys, or towards the court for player 2.
+                Key::Char('d') if humans[0] => game.serve(Player::One),
+                Key::Char(' ') if alone => game.serve(Player::One),
+                Key::ArrowLeft if humans[1] => game.serve(Player::Two),
+                Key::Char('c') if humans[2] => game.serve(Player::Three),
+                Key::Char('b') if humans[3] => game.serve(Player::Four),
+                Key::Char('q') => reason = Some(EndReason::Quit),
+                _ => (),
+            }
+        }
+
+        if let Some(reason) = reason {
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break reason;
+        }
+
+        for cpu in &mut cpus {
+            cpu.tick(&mut game);
+        }
+
+        if game.tick() == TickStatus::Exit {
+            sender.send(ChannelMessage::Stop).unwrap();
+
+            break EndReason::GameOver;
+        }
+    };
+
+    let screen = render_thread.join().unwrap();
+
+    (unshare(game), reason, screen)
+}
+
+// Takes a game back out of its mutex once every other thread is done with it.
+pub(crate) fn unshare(game: Arc<Mutex<Game>>) -> Game {
+    Arc::try_unwrap(game)
+        .ok()
+        .expect("the game is still shared")
+        .into_inner()
+        .unwrap()
+}
+
+// Renders a game every 10 ms on a separate thread, until it gets told to stop.
+// The screen is handed back once it's done.
+pub(crate) fn spawn_renderer(
+    mut screen: Screen,
+    game: Arc<Mutex<Game>>,
+    mode: Mode,
+) -> (Sender<ChannelMessage>, JoinHandle<Screen>) {
+    let (sender, receiver) = mpsc::channel();
+
+    // Some extra information to show in the middle of the status bar.
+    let mut note = String::new();
+
+    let render_thread = thread::spawn(move || loop {
+        thread::sleep(RENDER_TIME);
+
+        let game = game.lock().unwrap();
+
+        // This is checked after locking the game, so a finished game never gets drawn.
+        for msg in receiver.try_iter() {
+            match msg {
+                ChannelMessage::Stop => return screen,
+                ChannelMessage::Note(text) => note = text,
+            }
+        }
+
+        screen.reset();
+
+        for obstacle in &game.obstacles {
+            screen.rectangle(
+                obstacle.x,
+                obstacle.y,
+                obstacle.x + obstacle.width,
+                obstacle.y + obstacle.height,
+                Tile::new(FgColor::Default, BgColor::White, b' '),
+            );
+        }
+
+        for power_up in &game.power_ups {
+            screen.rectangle(
+                power_up.x,
+                power_up.y,
+                power_up.x + 1,
+                power_up.y + 1,
+                power_up.kind.tile(),
+            );
+        }
+
+        for player in PLAYERS {
+            if game.in_play(player) {
+                draw_paddle(&mut screen, &game, player);
+            } else if game.lives.is_some() {
+                draw_wall(&mut screen, &game, player);
+            }
+        }
+
+        for ball in &game.balls {
+            screen.set(
+                ball.x.round() as usize,
+                ball.y.round() as usize,
+                Tile::new(FgColor::Default, BgColor::Default, b'o'),
+            );
+        }
+
+        // Count down the seconds until the server can serve in the middle of the court,
+        // and then let them know how to once they can.
+        // CPUs serve straight away, so they don't need telling.
+        let text = match (game.serving, game.serve_timer) {
+            (false, _) => None,
+            (true, 0) => serve_key(mode, game.server)
+                .map(|key| format!("{} to serve ({key})", mode.player_name(game.server))),
+            (true, timer) => Some(
+                (timer as u128 * TICK_TIME.as_millis())
+                    .div_ceil(1000)
+                    .to_string(),
+            ),
+        };
+
+        if let Some(text) = text {
+            screen.text(
+                (game.width / 2).saturating_sub(text.len() / 2),
+                (game.height / 2).saturating_sub(2),
+                &text,
+                FgColor::Yellow,
+                BgColor::Default,
+            );
+        }
+
+        let mut middle = match game.lives {
+            Some(_) => format!("Free-for-all, {} lives each", game.rules.lives),
+            None => format!(
+                "Game {} of {}, first to {}",
+                game.game_number(),
+                game.rules.best_of,
+                game.rules.points_to_win
+            ),
+        };
+
+        if game
+            .effects
+            .iter()
+            .any(|effect| effect.kind == PowerUpKind::SpeedUp)
+        {
+            middle = format!("{middle} | Fast!");
+        }
+
+        if !note.is_empty() {
+            middle = format!("{middle} | {note}");
+        }
+
+        // In a free-for-all, players one and three are listed on the left, and two and four on the right.
+        let (left, right) = match game.lives {
+            Some(lives) => {
+                let standing = |player: Player| match lives[player.index()] {
+                    0 => format!(
+                        "{}: out{}",
+                        mode.player_name(player),
+                        effects(&game, player)
+                    ),
+                    left => format!(
+                        "{}: {left} lives{}",
+                        mode.player_name(player),
+                        effects(&game, player)
+                    ),
+                };
+
+                (
+                    format!("{} | {}", standing(Player::One), standing(Player::Three)),
+                    format!("{} | {}", standing(Player::Four), standing(Player::Two)),
+                )
+            }
+            None => (
+                format!(
+                    "{}: {} ({} won){}",
+                    mode.player_name(Player::One),
+                    game.score_1,
+                    game.games_1,
+                    effects(&game, Player::One)
+                ),
+                format!(
+                    "{}: {} ({} won){}",
+                    mode.player_name(Player::Two),
+                    game.score_2,
+                    game.games_2,
+                    effects(&game, Player::Two)
+                ),
+            ),
+        };
+
+        screen.render(&spread(game.width, &left, &middle, &right));
+    });
+
+    (sender, render_thread)
+}
+
+// The key a player serves with, to tell them about when it's their turn, or `None` for CPUs.
+fn serve_key(mode: Mode, player: Player) -> Option<&'static str> {
+    match (mode, player) {
+        (Mode::Cpu(_), Player::Two) => None,
+        (Mode::FreeForAll(seats), player) if seats[player.index()] == Seat::Cpu => None,
+        (Mode::Online, _) | (Mode::Cpu(_), Player::One) => Some("space"),
+        (_, Player::One) => Some("d"),
+        (_, Player::Two) => Some("left"),
+        (_, Player::Three) => Some("c"),
+        (_, Player::Four) => Some("b"),
+    }
+}
+
+// Lists the power-ups affecting a player, to go after their score.
+fn effects(game: &Game, player: Player) -> String {
+    game.effects
+        .iter()
+        .filter(|effect| effect.target == Some(player))
+        .map(|effect| format!(" [{}]", effect.kind.to_str()))
+        .collect()
+}
+
+// Draws a player's paddle on their side of the court.
+fn draw_paddle(screen: &mut Screen, game: &Game, player: Player) {
+    let position = game.paddle(player).tile();
+    let size = game.paddle_size(player);
+    let symbol = if player.horizontal() { b'-' } else { b'|' };
+    let color = match player {
+        Player::One => BgColor::Red,
+        Player::Two => BgColor::Blue,
+        Player::Three => BgColor::Green,
+        Player::Four => BgColor::Yellow,
+    };
+    let tile = Tile::new(FgColor::Default, color, symbol);
+    let (from, to) = (position.saturating_sub(size), position + size + 1);
+
+    match player {
+        Player::One => screen.rectangle(PADDLE_PADDING, from, PADDLE_PADDING + 1, to, tile),
+        Player::Two => screen.rectangle(
+            game.width - PADDLE_PADDING - 1,
+            from,
+            game.width - PADDLE_PADDING,
+            to,
+            tile,
+        ),
+        Player::Three => screen.rectangle(from, 0, to, 1, tile),
+        Player::Four => screen.rectangle(from, game.height, to, game.height + 1, tile),
+    }
+}
+
+// Draws the wall that takes the place of a player who's been knocked out of a free-for-all.
+fn draw_wall(screen: &mut Screen, game: &Game, player: Player) {
+    let tile = Tile::new(FgColor::Default, BgColor::White, b' ');
+
+    match player {
+        Player::One => screen.rectangle(0, 0, 1, game.height + 1, tile),
+        Player::Two => screen.rectangle(game.width - 1, 0, game.width, game.height + 1, tile),
+        Player::Three => screen.rectangle(0, 0, game.width, 1, tile),
+        Player::Four => screen.rectangle(0, game.height, game.width, game.height + 1, tile),
+    }
+}
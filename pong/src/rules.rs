@@ -0,0 +1,63 @@
+// Who serves after each rally.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ServeRule {
+    // Everyone takes turns.
+    Alternate,
+    // Whoever lost the last point serves.
+    Loser,
+}
+
+// The rules a match is played by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MatchRules {
+    // How many points it takes to win a game.
+    pub points_to_win: u32,
+
+    // How many points a player has to be ahead by to win a game, 2 means you have to win by two.
+    pub win_by: u32,
+
+    // How many games the match is played over. Whoever wins the majority of them wins the match.
+    pub best_of: u32,
+
+    // How many ticks the ball is held at the server's paddle before they can serve it.
+    pub serve_delay: u32,
+
+    pub serve: ServeRule,
+
+    // Whether power-ups show up on the court, see the `modifiers` module.
+    pub power_ups: bool,
+
+    // Chaos pong, where another ball gets served every time this many rallies have been played. 0 turns it off.
+    pub chaos: u32,
+
+    // How many lives everyone starts with in a free-for-all.
+    pub lives: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            points_to_win: 11,
+            win_by: 2,
+            best_of: 3,
+            // About 1.5 seconds with a 60 ms tick.
+            serve_delay: 25,
+            serve: ServeRule::Alternate,
+            power_ups: false,
+            chaos: 0,
+            lives: 3,
+        }
+    }
+}
+
+impl MatchRules {
+    // Checks whether a player with `score` points has won the game against someone with `other`.
+    pub fn won_game(&self, score: u32, other: u32) -> bool {
+        score >= self.points_to_win && score >= other + self.win_by
+    }
+
+    // How many games a player needs to win to take the match.
+    pub fn games_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+}
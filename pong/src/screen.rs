@@ -2,6 +2,8 @@ use std::io::{self, Write};
 
 use console::Term;
 
+use crate::spectate::Server;
+
 pub enum FgColor {
     Black,
     Red,
@@ -89,6 +91,9 @@ pub struct Screen {
     initial: Vec<Vec<[u8; 11]>>,
 
     stdout: std::io::Stdout,
+
+    // Where every rendered frame gets sent, if anyone is watching.
+    spectators: Option<Server>,
 }
 
 impl Screen {
@@ -108,6 +113,7 @@ impl Screen {
             width,
             initial,
             stdout: io::stdout(),
+            spectators: None,
         };
 
         screen.initial_draw();
@@ -122,7 +128,12 @@ impl Screen {
         self.bg_color(Tile::new(FgColor::Default, BgColor::Default, b' '));
     }
 
-    pub fn render(&self, status: &str) {
+    // Sends every frame rendered from now on to a spectator server.
+    pub fn share(&mut self, server: Server) {
+        self.spectators = Some(server);
+    }
+
+    pub fn render(&mut self, status: &str) {
         // Rushing out the output is actually faster than collecting it and then outputting it, and it leads to less flicker.
 
         // We can lock the standard output which allows us to print to it as much as we
@@ -149,6 +160,12 @@ impl Screen {
         // Flushes everything and cleans everything out.
         // It improves a couple of things, like making sure the status bar is printed out properly.
         lock.flush().unwrap();
+
+        if let Some(server) = &mut self.spectators {
+            let tiles: Vec<u8> = self.data.iter().flatten().flatten().copied().collect();
+
+            server.broadcast(self.width, self.height, &tiles, status);
+        }
     }
 
     // Takes the initial state of the screen and loads it into the current state.
@@ -158,7 +175,7 @@ impl Screen {
     }
 
     // Sets actual bytes instead of using the abstracted tile.
-    fn set_bytes(&mut self, x: usize, y: usize, tile: [u8; 11]) {
+    pub(crate) fn set_bytes(&mut self, x: usize, y: usize, tile: [u8; 11]) {
         self.data[y][x] = tile;
     }
 
@@ -167,12 +184,31 @@ impl Screen {
         self.set_bytes(x, y, tile.calc());
     }
 
+    // Writes out some text starting at a position, one tile per character.
+    // Anything that goes past the edge of the screen is cut off.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, fg_color: FgColor, bg_color: BgColor) {
+        if y >= self.height {
+            return;
+        }
+
+        let mut tile = Tile::new(fg_color, bg_color, b' ').calc();
+
+        for (i, character) in text.bytes().enumerate() {
+            if x + i >= self.width {
+                break;
+            }
+
+            tile[10] = character;
+            self.set_bytes(x + i, y, tile);
+        }
+    }
+
     // Draws a horizontal line across the screen.
     pub fn line(&mut self, y: usize, tile: Tile) {
         self.data[y] = std::vec::from_elem(tile.calc(), self.width);
     }
 
-    // Draws a rectangle
+    // Draws a rectangle, cutting off anything that goes past the edge of the screen.
     pub fn rectangle(
         &mut self,
         from_x: usize,
@@ -183,8 +219,8 @@ impl Screen {
     ) {
         let calculated_tile = tile.calc();
 
-        for y in from_y..to_y {
-            for x in from_x..to_x {
+        for y in from_y..to_y.min(self.height) {
+            for x in from_x..to_x.min(self.width) {
                 self.set_bytes(x, y, calculated_tile)
             }
         }
@@ -0,0 +1,85 @@
+// Lets other people watch a game from another terminal. The actual sending and receiving is shared with
+// the other games, see the broadcast crate, so this is just the pong side of it.
+
+use std::{io, net::SocketAddr, sync::mpsc::RecvTimeoutError, time::Duration};
+
+use broadcast::{Frame, Watcher};
+use console::{Key, Term};
+use session::spawn_input;
+
+use crate::screen::Screen;
+
+pub use broadcast::Server;
+
+// Sent as soon as someone connects, so anything that isn't watching pong gets turned away straight away.
+const MAGIC: [u8; 4] = *b"PGTV";
+
+// How often the spectator checks for q while no frames are coming in.
+const KEY_TIME: Duration = Duration::from_millis(100);
+
+// Starts listening for spectators on an address, which should be on 127.0.0.1 unless they're on other computers.
+pub fn start(addr: SocketAddr) -> io::Result<Server> {
+    Server::start_on(addr, MAGIC)
+}
+
+fn draw(screen: &mut Screen, frame: &Frame) {
+    // The game might be bigger than this terminal, in which case the edges get cut off.
+    screen.reset();
+
+    for y in 0..frame.height.min(screen.height) {
+        for x in 0..frame.width.min(screen.width) {
+            if let Ok(tile) = frame.cell(x, y).try_into() {
+                screen.set_bytes(x, y, tile);
+            }
+        }
+    }
+
+    screen.render(&frame.status);
+}
+
+// Watches a game someone is broadcasting at `addr`, until they stop or the spectator presses q.
+pub fn watch(term: &Term, addr: &str) -> io::Result<()> {
+    let frames = Watcher::connect(addr, MAGIC)?.spawn();
+    let (keys, input_thread) = spawn_input(term);
+
+    term.hide_cursor().unwrap();
+
+    let mut screen = Screen::new(term);
+
+    // Nothing gets sent while the game's sitting on a menu, so there's no telling how long the first frame takes.
+    screen.render("Waiting for the game to start. Press q to quit.");
+
+    // Frames are read in their own thread, so q works straight away even while the game's gone quiet.
+    let result = loop {
+        if keys.try_iter().any(|key| key == Key::Char('q')) {
+            break Ok(());
+        }
+
+        match frames.recv_timeout(KEY_TIME) {
+            Ok(Ok(frame)) => draw(&mut screen, &frame),
+            Ok(Err(err)) => break Err(err),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break Ok(()),
+        }
+    };
+
+    // The input thread only stops once q is pressed, so it's waited on either way.
+    if result.is_err() {
+        screen.reset();
+        screen.render("The game stopped broadcasting. Press q to quit.");
+
+        for key in keys.iter() {
+            if key == Key::Char('q') {
+                break;
+            }
+        }
+    }
+
+    input_thread.join().unwrap();
+
+    match result {
+        // That's just the game closing normally.
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+        result => result,
+    }
+}
@@ -0,0 +1,179 @@
+// Numbers about how a match went, which get shown once it's over and can be saved for keeping track of a ladder.
+
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    game::{Game, Player, PLAYERS},
+    Mode, Seat, TICK_TIME,
+};
+
+// How many zones every paddle is split into when counting where it gets hit, from one end to the other.
+pub const ZONES: usize = 5;
+
+// Everything counted up while a match is played, besides the rallies which `Game` keeps itself.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Stats {
+    // How many times each player hit the ball with each zone of their paddle, in `Player::index` order.
+    pub hits: [[u32; ZONES]; 4],
+
+    // How many points were played while each player was serving, and how many of them they won.
+    // In a free-for-all, a point is won whenever someone else loses a life.
+    pub served: [u32; 4],
+    pub won_on_serve: [u32; 4],
+
+    // The fastest any ball went, in tiles per tick.
+    pub top_speed: f32,
+}
+
+impl Stats {
+    // Counts a hit `offset` tiles from the middle of a paddle that reaches `size` tiles either side of it.
+    pub fn hit(&mut self, player: Player, offset: f32, size: usize) {
+        let tiles = size * 2 + 1;
+        let tile = (offset + size as f32).clamp(0.0, tiles as f32 - 1.0) as usize;
+
+        self.hits[player.index()][tile * ZONES / tiles] += 1;
+    }
+
+    // Counts a point that `loser` lost while `server` was serving.
+    pub fn point(&mut self, server: Player, loser: Player) {
+        self.served[server.index()] += 1;
+
+        if loser != server {
+            self.won_on_serve[server.index()] += 1;
+        }
+    }
+
+    pub fn speed(&mut self, speed: f32) {
+        self.top_speed = self.top_speed.max(speed);
+    }
+}
+
+// The players who took part in a match.
+fn players(game: &Game, mode: Mode) -> Vec<Player> {
+    PLAYERS
+        .into_iter()
+        .filter(|player| match mode {
+            Mode::FreeForAll(seats) => seats[player.index()] != Seat::Empty,
+            _ => game.lives.is_some() || !player.horizontal(),
+        })
+        .collect()
+}
+
+pub fn longest_rally(game: &Game) -> u32 {
+    game.rallies.iter().max().copied().unwrap_or(0)
+}
+
+pub fn average_rally(game: &Game) -> f32 {
+    if game.rallies.is_empty() {
+        0.0
+    } else {
+        game.rallies.iter().sum::<u32>() as f32 / game.rallies.len() as f32
+    }
+}
+
+// The fastest any ball went, in tiles per second.
+pub fn top_speed(game: &Game) -> f32 {
+    game.stats.top_speed / TICK_TIME.as_secs_f32()
+}
+
+// The stats as lines of text, for the match-over screen.
+pub fn summary(game: &Game, mode: Mode) -> Vec<String> {
+    let mut lines = vec![
+        format!("Rallies: {}", game.rallies.len()),
+        format!("Longest rally: {} hits", longest_rally(game)),
+        format!("Average rally: {:.1} hits", average_rally(game)),
+        format!("Top speed: {:.0} tiles a second", top_speed(game)),
+        String::new(),
+        String::from("Points won on serve, and hits from one end of the paddle to the other:"),
+    ];
+
+    for player in players(game, mode) {
+        let index = player.index();
+        let hits: Vec<String> = game.stats.hits[index].iter().map(u32::to_string).collect();
+
+        lines.push(format!(
+            "{}: {} of {} | {}",
+            mode.player_name(player),
+            game.stats.won_on_serve[index],
+            game.stats.served[index],
+            hits.join(" / ")
+        ));
+    }
+
+    lines
+}
+
+// Writes down the stats for a match as a JSON object, where `finished` is when it finished in seconds since 1970.
+pub fn to_json(game: &Game, mode: Mode, finished: u64) -> String {
+    let winner = match game.winner {
+        Some(player) => quote(mode.player_name(player)),
+        None => String::from("null"),
+    };
+
+    let players: Vec<String> = players(game, mode)
+        .into_iter()
+        .map(|player| {
+            let index = player.index();
+
+            // A free-for-all is played for lives instead of points and games.
+            let score = match (game.lives, player) {
+                (Some(lives), _) => format!("\"lives\":{}", lives[index]),
+                (None, Player::One) => {
+                    format!("\"points\":{},\"games\":{}", game.points_1, game.games_1)
+                }
+                (None, _) => format!("\"points\":{},\"games\":{}", game.points_2, game.games_2),
+            };
+
+            format!(
+                "{{\"name\":{},{score},\"served\":{},\"won_on_serve\":{},\"hits\":{:?}}}",
+                quote(mode.player_name(player)),
+                game.stats.served[index],
+                game.stats.won_on_serve[index],
+                game.stats.hits[index],
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"finished\":{finished},\"winner\":{winner},\"players\":[{}],\"rallies\":{},\"longest_rally\":{},\"average_rally\":{:.2},\"top_speed\":{:.2}}}",
+        players.join(","),
+        game.rallies.len(),
+        longest_rally(game),
+        average_rally(game),
+        top_speed(game),
+    )
+}
+
+// Adds the stats for a finished match to the end of a file, with every match on its own line.
+pub fn export(path: &str, game: &Game, mode: Mode) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let finished = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0);
+
+    writeln!(file, "{}", to_json(game, mode, finished))
+}
+
+// Quotes a string for JSON.
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => quoted.push(character),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
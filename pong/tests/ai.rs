@@ -0,0 +1,179 @@
+// Checks where the CPU expects the ball to end up, and that it plays within the limits of its difficulty.
+
+use pong::{
+    ai::{predict, Cpu, Difficulty},
+    game::{paddle_scale, Ball, Game, Player},
+    rules::MatchRules,
+};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+// A game with one ball in play, already served.
+fn game_with_ball(x: f32, y: f32, velocity_x: f32, velocity_y: f32) -> Game {
+    let mut game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+
+    game.serving = false;
+    game.balls = vec![Ball {
+        x,
+        y,
+        velocity_x,
+        velocity_y,
+        owner: None,
+    }];
+    game
+}
+
+// Plays the game until the ball gets to player 2's paddle, and gives back where it is along it.
+// The ball has to miss the paddle, or it'd bounce off before getting there.
+fn row_at_paddle_2(game: &mut Game) -> f32 {
+    let face = game.paddle_2_face();
+
+    while game.balls[0].x < face {
+        game.tick();
+    }
+
+    game.balls[0].y
+}
+
+#[test]
+fn predicts_a_straight_shot() {
+    let game = game_with_ball(40.0, 5.0, 1.0, 0.0);
+
+    assert_eq!(predict(&game, Player::Two), Some((33.0, 5.0)));
+}
+
+#[test]
+fn predicts_bounces_off_the_walls() {
+    // Where the ball starts going up and down, and where it should end up after bouncing off the top and bottom.
+    let shots = [
+        // Off the bottom.
+        (20.0, 0.8, 1.6),
+        // Off the top.
+        (6.0, -0.3, 3.9),
+        // Off the top and then the bottom.
+        (4.0, -1.5, 2.5),
+        // Off the bottom, the top and the bottom again.
+        (12.0, 2.0, 18.0),
+    ];
+
+    for (y, velocity_y, expected) in shots {
+        let mut game = game_with_ball(40.0, y, 1.0, velocity_y);
+        let (ticks, predicted) = predict(&game, Player::Two).unwrap();
+
+        assert_eq!(ticks, 33.0);
+        assert!(
+            (predicted - expected).abs() < 0.001,
+            "predicted {predicted} instead of {expected} for a ball going {velocity_y} from {y}"
+        );
+
+        // The ball really does end up there.
+        let actual = row_at_paddle_2(&mut game);
+
+        assert!(
+            (predicted - actual).abs() < 0.01,
+            "predicted {predicted}, but the ball got to {actual}"
+        );
+    }
+}
+
+#[test]
+fn predicts_the_top_and_bottom_paddles_too() {
+    let mut game = game_with_ball(70.0, 12.0, 3.0, 1.0);
+
+    game.free_for_all(&[Player::Three, Player::Four]);
+
+    // 11 ticks to the bottom paddle, which takes it 33 columns right, so 24 past the right side and back.
+    let (ticks, predicted) = predict(&game, Player::Four).unwrap();
+
+    assert_eq!(ticks, 11.0);
+    assert!((predicted - 55.0).abs() < 0.001);
+}
+
+#[test]
+fn ignores_balls_going_the_other_way() {
+    let game = game_with_ball(40.0, 12.0, -1.0, 0.5);
+
+    assert_eq!(predict(&game, Player::Two), None);
+}
+
+#[test]
+fn predicts_whichever_ball_gets_there_first() {
+    let mut game = game_with_ball(40.0, 5.0, 1.0, 0.0);
+
+    game.balls.push(Ball {
+        x: 60.0,
+        y: 20.0,
+        velocity_x: 1.0,
+        velocity_y: 0.0,
+        owner: None,
+    });
+
+    assert_eq!(predict(&game, Player::Two), Some((13.0, 20.0)));
+}
+
+#[test]
+fn cpus_stay_under_their_max_speed() {
+    for difficulty in DIFFICULTIES {
+        let mut game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 7);
+
+        game.ball_initial_pos();
+
+        let mut cpus = [
+            Cpu::new(difficulty, Player::One, &game),
+            Cpu::new(difficulty, Player::Two, &game),
+        ];
+
+        // Long enough for a few rallies.
+        for _ in 0..2000 {
+            for cpu in &mut cpus {
+                cpu.tick(&mut game);
+            }
+
+            game.tick();
+
+            for player in [Player::One, Player::Two] {
+                let speed = game.paddle(player).velocity.abs();
+
+                assert!(
+                    speed <= difficulty.max_speed() * paddle_scale(player),
+                    "a CPU on {} went {speed} rows per tick",
+                    difficulty.to_str()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cpus_wait_for_their_reaction_delay() {
+    for difficulty in DIFFICULTIES {
+        // Heading for the top of player 2's side, well away from their paddle in the middle.
+        let mut game = game_with_ball(20.0, 2.0, 1.0, 0.0);
+        let mut cpu = Cpu::new(difficulty, Player::Two, &game);
+        let start = game.paddle(Player::Two).position;
+
+        for _ in 1..difficulty.reaction_delay() {
+            cpu.tick(&mut game);
+            game.tick();
+
+            assert_eq!(
+                game.paddle(Player::Two).position,
+                start,
+                "a CPU on {} moved before reacting",
+                difficulty.to_str()
+            );
+        }
+
+        cpu.tick(&mut game);
+        game.tick();
+
+        assert!(
+            game.paddle(Player::Two).position < start,
+            "a CPU on {} didn't go for the ball once it reacted",
+            difficulty.to_str()
+        );
+    }
+}
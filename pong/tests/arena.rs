@@ -0,0 +1,60 @@
+// Checks where arena obstacles end up on the court.
+
+use pong::{
+    arena::Arena,
+    game::{Game, Player},
+    rules::MatchRules,
+};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// Obstacles all over the place, apart from a gap in the middle of every row.
+const MAP: &str = "###.###\n###.###\n###.###\n###.###";
+
+#[test]
+fn obstacles_cover_the_whole_height_between_two_players() {
+    let game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+    let obstacles = Arena::parse(MAP).unwrap().obstacles(&game);
+
+    assert!(obstacles.iter().any(|obstacle| obstacle.y == 0));
+    assert!(obstacles
+        .iter()
+        .any(|obstacle| obstacle.y + obstacle.height == HEIGHT));
+}
+
+#[test]
+fn obstacles_stay_clear_of_the_top_and_bottom_paddles() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+
+    game.free_for_all(&[Player::One, Player::Two, Player::Three, Player::Four]);
+
+    let obstacles = Arena::parse(MAP).unwrap().obstacles(&game);
+    let top = game.paddle_3_face() as usize;
+    let bottom = game.paddle_4_face() as usize;
+
+    assert!(!obstacles.is_empty());
+
+    // There's a row of space between the obstacles and the paddles' faces, the same as next to the left and right paddles.
+    for obstacle in obstacles {
+        assert!(obstacle.y > top + 1, "{obstacle:?} is too close to the top");
+        assert!(
+            obstacle.y + obstacle.height < bottom,
+            "{obstacle:?} is too close to the bottom"
+        );
+    }
+}
+
+#[test]
+fn empty_seats_are_walls_obstacles_can_touch() {
+    let mut game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+
+    game.free_for_all(&[Player::One, Player::Two, Player::Three]);
+
+    let obstacles = Arena::parse(MAP).unwrap().obstacles(&game);
+
+    assert!(obstacles.iter().all(|obstacle| obstacle.y > 2));
+    assert!(obstacles
+        .iter()
+        .any(|obstacle| obstacle.y + obstacle.height == HEIGHT));
+}
@@ -0,0 +1,109 @@
+// Checks that every ball scores by itself, that a rally lasts until the last ball is out, and how many balls chaos pong serves.
+
+use pong::{
+    game::{Ball, Game},
+    rules::MatchRules,
+    MAX_BALLS,
+};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// A ball in the middle of the court that isn't going anywhere.
+const STILL: Ball = Ball {
+    x: 40.0,
+    y: 12.0,
+    velocity_x: 0.0,
+    velocity_y: 0.0,
+    owner: None,
+};
+
+// A ball about to go out on player 1's side, well above their paddle.
+const MISSED: Ball = Ball {
+    x: 0.5,
+    y: 2.0,
+    velocity_x: -1.0,
+    velocity_y: 0.0,
+    owner: None,
+};
+
+fn game_with_balls(balls: Vec<Ball>, rules: MatchRules) -> Game {
+    let mut game = Game::with_size(WIDTH, HEIGHT, rules, 0);
+
+    game.serving = false;
+    game.balls = balls;
+    game
+}
+
+// How many balls get served after `rallies` rallies have been played.
+fn balls_after(rallies: usize, chaos: u32) -> usize {
+    let rules = MatchRules {
+        chaos,
+        ..MatchRules::default()
+    };
+    let mut game = Game::with_size(WIDTH, HEIGHT, rules, 0);
+
+    game.rallies = vec![0; rallies];
+    game.ball_initial_pos();
+    game.balls.len()
+}
+
+#[test]
+fn the_rally_goes_on_while_a_ball_is_left() {
+    let mut game = game_with_balls(vec![MISSED, STILL], MatchRules::default());
+
+    game.tick();
+
+    // The ball that went out still counts, but the one in the middle is still in play.
+    assert_eq!((game.score_1, game.score_2), (0, 1));
+    assert_eq!(game.balls.len(), 1);
+    assert!(!game.serving);
+    assert!(game.rallies.is_empty());
+
+    // Once that one's gone too, the rally's over.
+    game.balls[0] = MISSED;
+    game.tick();
+
+    assert_eq!((game.score_1, game.score_2), (0, 2));
+    assert!(game.serving);
+    assert_eq!(game.rallies.len(), 1);
+}
+
+#[test]
+fn every_ball_that_goes_out_scores() {
+    let mut game = game_with_balls(vec![MISSED, MISSED, MISSED], MatchRules::default());
+
+    game.tick();
+
+    assert_eq!((game.score_1, game.score_2), (0, 3));
+    assert_eq!(game.rallies.len(), 1);
+}
+
+#[test]
+fn winning_a_game_ends_the_rally_straight_away() {
+    let mut game = game_with_balls(vec![MISSED, MISSED, STILL], MatchRules::default());
+
+    game.score_2 = 10;
+    game.tick();
+
+    // The second ball going out doesn't count towards the next game.
+    assert_eq!((game.games_1, game.games_2), (0, 1));
+    assert_eq!((game.score_1, game.score_2), (0, 0));
+    assert!(game.serving);
+}
+
+#[test]
+fn one_ball_without_chaos() {
+    for rallies in [0, 1, 5, 50] {
+        assert_eq!(balls_after(rallies, 0), 1);
+    }
+}
+
+#[test]
+fn chaos_adds_a_ball_every_few_rallies() {
+    assert_eq!(balls_after(0, 3), 1);
+    assert_eq!(balls_after(2, 3), 1);
+    assert_eq!(balls_after(3, 3), 2);
+    assert_eq!(balls_after(7, 3), 3);
+    assert_eq!(balls_after(100, 3), MAX_BALLS);
+}
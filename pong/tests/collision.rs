@@ -0,0 +1,111 @@
+// Checks that balls bounce off the paddles however fast they're going, instead of skipping past them in one tick.
+
+use pong::{
+    game::{Ball, Game, Player},
+    rules::MatchRules,
+    MAX_BALL_SPEED,
+};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// A game with one ball in play, already served.
+fn game_with_ball(ball: Ball) -> Game {
+    let mut game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+
+    game.serving = false;
+    game.balls = vec![ball];
+    game
+}
+
+// A ball a few columns in front of a player's paddle, heading for it fast enough to end up off the court
+// after one tick if nothing stops it. `row` is how far from the middle of the paddle it's heading.
+fn fast_ball(game: &Game, player: Player, row: f32) -> Ball {
+    let face = game.paddle_face(player);
+    let y = game.paddle(player).position + row;
+
+    let (x, velocity_x) = match player {
+        Player::One => (face + 3.0, -(face + 6.0)),
+        _ => (face - 3.0, WIDTH as f32 - face + 3.0),
+    };
+
+    Ball {
+        x,
+        y,
+        velocity_x,
+        velocity_y: 0.0,
+        owner: None,
+    }
+}
+
+#[test]
+fn fast_balls_bounce_off_the_paddle() {
+    for player in [Player::One, Player::Two] {
+        let game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+        let ball = fast_ball(&game, player, 0.0);
+        let heading = ball.velocity_x.signum();
+
+        // Much faster than the ball is ever allowed to go, and further than the whole paddle in one tick.
+        assert!(ball.velocity_x.abs() > MAX_BALL_SPEED);
+
+        let mut game = game_with_ball(ball);
+        game.tick();
+
+        assert_eq!(game.balls.len(), 1, "{player:?}'s paddle got skipped");
+        assert_eq!(game.rally, 1);
+        assert_eq!((game.score_1, game.score_2), (0, 0));
+
+        // It's heading back the way it came, and it's still on the court.
+        let ball = game.balls[0];
+
+        assert_eq!(ball.velocity_x.signum(), -heading);
+        assert!((0.0..WIDTH as f32).contains(&ball.x), "{ball:?}");
+    }
+}
+
+#[test]
+fn fast_balls_bounce_off_the_edges_of_the_paddle() {
+    let game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+    let size = game.paddle_size(Player::One) as f32;
+
+    for row in [-size, size] {
+        let mut game = game_with_ball(fast_ball(&game, Player::One, row));
+        game.tick();
+
+        assert_eq!(game.balls.len(), 1, "missed {row} rows from the middle");
+        assert!(game.balls[0].velocity_x > 0.0);
+    }
+}
+
+#[test]
+fn fast_balls_past_the_paddle_go_out() {
+    let game = Game::with_size(WIDTH, HEIGHT, MatchRules::default(), 0);
+    let size = game.paddle_size(Player::One) as f32;
+
+    let mut game = game_with_ball(fast_ball(&game, Player::One, size + 2.0));
+    game.tick();
+
+    assert_eq!(game.rally, 0);
+    assert_eq!((game.score_1, game.score_2), (0, 1));
+}
+
+#[test]
+fn fast_balls_stay_in_short_courts() {
+    // The ball goes so far in one tick that it bounces off the bottom and then the top before it's back inside.
+    let mut game = Game::with_size(WIDTH, 2, MatchRules::default(), 0);
+
+    game.serving = false;
+    game.balls = vec![Ball {
+        x: 40.0,
+        y: 1.0,
+        velocity_x: 0.5,
+        velocity_y: 7.3,
+        owner: None,
+    }];
+    game.tick();
+
+    let ball = game.balls[0];
+
+    assert!((0.0..=2.0).contains(&ball.y), "{ball:?}");
+    assert_eq!((game.score_1, game.score_2), (0, 0));
+}
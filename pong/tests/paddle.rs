@@ -0,0 +1,82 @@
+// Checks that paddles move smoothly the way keys actually arrive from a terminal, which only sends a key again
+// after a pause and then keeps repeating it, and never says when it's let go.
+
+use std::time::Duration;
+
+use pong::{
+    paddle::{Paddle, FIRST_HOLD_TICKS, REPEAT_DELAY},
+    TICK_TIME,
+};
+
+// How often a held key repeats once it gets going, which is about as slow as terminals go.
+const REPEAT_RATE: Duration = Duration::from_millis(50);
+
+// Plays `ticks` ticks of a key being held down for `held`, and gives back the paddle's velocity after every tick.
+// The key repeats `delay` after it's pressed.
+fn hold(held: Duration, delay: Duration, ticks: u32) -> Vec<f32> {
+    let mut paddle = Paddle::new(100.0);
+    let mut presses = vec![Duration::ZERO];
+    let mut press = delay;
+
+    while press < held {
+        presses.push(press);
+        press += REPEAT_RATE;
+    }
+
+    (0..ticks)
+        .map(|tick| {
+            // Every key that came in during the last tick gets handled before it's played.
+            let end = TICK_TIME * (tick + 1);
+            let start = end - TICK_TIME;
+
+            for _ in presses.iter().filter(|press| (start..end).contains(press)) {
+                paddle.push(1);
+            }
+
+            paddle.tick(0.0, 200.0, 1.0);
+            paddle.velocity
+        })
+        .collect()
+}
+
+#[test]
+fn the_first_hold_lasts_until_the_key_repeats() {
+    assert!(TICK_TIME * FIRST_HOLD_TICKS as u32 > REPEAT_DELAY);
+}
+
+#[test]
+fn held_keys_never_stop_the_paddle() {
+    for delay in [250, 400, 500] {
+        let velocities = hold(Duration::from_secs(2), Duration::from_millis(delay), 50);
+        let held = (Duration::from_secs(2).as_millis() / TICK_TIME.as_millis()) as usize;
+
+        // It never slows down while the key's held, even while waiting for it to repeat.
+        for pair in velocities[..held].windows(2) {
+            assert!(
+                pair[1] >= pair[0],
+                "slowed down with a {delay} ms delay: {velocities:?}"
+            );
+        }
+
+        // And it slides to a stop once it's let go.
+        assert_eq!(velocities.last(), Some(&0.0), "{velocities:?}");
+    }
+}
+
+#[test]
+fn taps_move_the_paddle_a_little() {
+    let mut paddle = Paddle::new(100.0);
+
+    paddle.push(1);
+
+    for _ in 0..FIRST_HOLD_TICKS * 2 {
+        paddle.tick(0.0, 200.0, 1.0);
+    }
+
+    assert_eq!(paddle.velocity, 0.0);
+    assert!(
+        paddle.position > 100.0 && paddle.position < 105.0,
+        "{}",
+        paddle.position
+    );
+}
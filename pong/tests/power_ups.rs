@@ -0,0 +1,71 @@
+// Checks who gets hit by the power-ups that go against someone else.
+
+use pong::{
+    game::{Ball, Game, Player},
+    modifiers::{PowerUp, PowerUpKind},
+    rules::MatchRules,
+};
+
+// A game where player 1 is about to run the ball into a power-up.
+fn about_to_pick_up(kind: PowerUpKind, seed: u64) -> Game {
+    let mut game = Game::with_size(80, 24, MatchRules::default(), seed);
+
+    game.serving = false;
+    game.balls = vec![Ball {
+        x: 40.0,
+        y: 12.0,
+        velocity_x: 1.0,
+        velocity_y: 0.0,
+        owner: Some(Player::One),
+    }];
+    game.power_ups = vec![PowerUp { kind, x: 41, y: 12 }];
+    game
+}
+
+// Who a power-up ended up affecting.
+fn target(game: &Game, kind: PowerUpKind) -> Option<Player> {
+    game.effects
+        .iter()
+        .find(|effect| effect.kind == kind)
+        .expect("the power-up wasn't picked up")
+        .target
+}
+
+#[test]
+fn the_other_player_gets_hit() {
+    for kind in [PowerUpKind::Shrink, PowerUpKind::Reverse] {
+        let mut game = about_to_pick_up(kind, 0);
+
+        game.tick();
+
+        assert_eq!(target(&game, kind), Some(Player::Two));
+    }
+}
+
+#[test]
+fn players_who_are_out_are_skipped() {
+    for kind in [PowerUpKind::Shrink, PowerUpKind::Reverse] {
+        let mut game = about_to_pick_up(kind, 0);
+
+        // Player 2 is across the court from player 1, but they've already been knocked out.
+        game.free_for_all(&[Player::One, Player::Two, Player::Three]);
+        game.lives = Some([3, 0, 3, 0]);
+        game.tick();
+
+        assert_eq!(target(&game, kind), Some(Player::Three));
+    }
+}
+
+#[test]
+fn someone_still_in_always_gets_hit() {
+    for seed in 0..20 {
+        let mut game = about_to_pick_up(PowerUpKind::Shrink, seed);
+
+        game.free_for_all(&[Player::One, Player::Three, Player::Four]);
+        game.tick();
+
+        let target = target(&game, PowerUpKind::Shrink).unwrap();
+
+        assert!(target == Player::Three || target == Player::Four);
+    }
+}
@@ -0,0 +1,124 @@
+// Checks that every message comes out of the other end of the wire the same as it went in.
+
+use pong::{
+    arena::Obstacle,
+    net::{
+        protocol::{
+            Message, MAX_INPUTS, MAX_OBSTACLES, MAX_PACKET, OBSTACLE_SIZE, PROTOCOL_VERSION,
+            WELCOME_HEADER,
+        },
+        rollback::Input,
+    },
+    rules::{MatchRules, ServeRule},
+};
+
+// Encodes a message and decodes it again, checking that it fits in a packet along the way.
+fn round_trip(message: &Message) -> Message {
+    let bytes = message.encode();
+
+    assert!(
+        bytes.len() <= MAX_PACKET,
+        "{message:?} is {} bytes",
+        bytes.len()
+    );
+
+    Message::decode(&bytes).unwrap()
+}
+
+#[test]
+fn welcome_with_the_most_obstacles_fits() {
+    let obstacles: Vec<Obstacle> = (0..MAX_OBSTACLES)
+        .map(|i| Obstacle {
+            x: i,
+            y: i * 2,
+            width: 1 + i % 3,
+            height: 2,
+        })
+        .collect();
+
+    let welcome = Message::Welcome {
+        width: 120,
+        height: 40,
+        rules: MatchRules {
+            points_to_win: 11,
+            win_by: 2,
+            best_of: 3,
+            serve: ServeRule::Loser,
+            power_ups: true,
+            chaos: 4,
+            ..MatchRules::default()
+        },
+        seed: u64::MAX - 1,
+        obstacles,
+    };
+
+    assert_eq!(
+        welcome.encode().len(),
+        WELCOME_HEADER + MAX_OBSTACLES * OBSTACLE_SIZE
+    );
+    assert_eq!(round_trip(&welcome), welcome);
+}
+
+#[test]
+fn every_message_round_trips() {
+    // Every kind of input there is, over and over until there's as many as fit in one message.
+    let inputs: Vec<Input> = [-1, 0, 1]
+        .into_iter()
+        .flat_map(|direction| [false, true].map(|serve| Input { direction, serve }))
+        .cycle()
+        .take(MAX_INPUTS)
+        .collect();
+
+    let messages = [
+        Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+        Message::Rejected { version: u16::MAX },
+        Message::Inputs {
+            ack: 12,
+            start: u32::MAX - MAX_INPUTS as u32,
+            inputs,
+        },
+        Message::Inputs {
+            ack: 0,
+            start: 0,
+            inputs: vec![],
+        },
+        Message::Checksum {
+            frame: 90,
+            checksum: 0x0123_4567_89ab_cdef,
+        },
+        Message::Ping { time: 1234 },
+        Message::Pong { time: u64::MAX },
+        Message::Quit,
+    ];
+
+    for message in messages {
+        assert_eq!(round_trip(&message), message);
+    }
+}
+
+#[test]
+fn broken_packets_are_errors() {
+    // Cut off partway through.
+    let checksum = Message::Checksum {
+        frame: 1,
+        checksum: 2,
+    }
+    .encode();
+
+    assert!(Message::decode(&checksum[..checksum.len() - 1]).is_err());
+    assert!(Message::decode(&[]).is_err());
+
+    // A kind of message that doesn't exist.
+    assert!(Message::decode(&[200]).is_err());
+
+    // A hello from something that isn't pong.
+    let mut hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+    }
+    .encode();
+    hello[1] = b'X';
+
+    assert!(Message::decode(&hello).is_err());
+}
@@ -0,0 +1,198 @@
+// Plays matches between two sessions over a pretend network, checking they end up with the same game
+// however late or lost the packets are.
+
+use std::collections::VecDeque;
+
+use pong::{
+    game::Player,
+    net::{
+        self,
+        protocol::Message,
+        rollback::{Input, Session},
+    },
+    rules::MatchRules,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+// How many frames each side plays.
+const FRAMES: u32 = 600;
+
+// Sends packets one way like a bad network would, by holding each one back a few ticks and losing some of them.
+struct Wire {
+    latency: u32,
+    loss: u32,
+    rng: StdRng,
+    queue: VecDeque<(u32, Vec<u8>)>,
+}
+
+impl Wire {
+    fn new(latency: u32, loss: u32, seed: u64) -> Wire {
+        Wire {
+            latency,
+            loss,
+            rng: StdRng::seed_from_u64(seed),
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn send(&mut self, tick: u32, message: Message) {
+        if self.rng.gen_range(0..100) >= self.loss {
+            self.queue
+                .push_back((tick + self.latency, message.encode()));
+        }
+    }
+
+    // Hands every packet that's arrived by `tick` to the session on the other end.
+    fn deliver(&mut self, tick: u32, session: &mut Session) {
+        while self.queue.front().is_some_and(|(due, _)| *due <= tick) {
+            let (_, packet) = self.queue.pop_front().unwrap();
+
+            match Message::decode(&packet).unwrap() {
+                Message::Inputs { ack, start, inputs } => {
+                    session.ack(ack);
+                    session.add_remote_inputs(start, &inputs);
+                }
+                Message::Checksum { frame, checksum } => {
+                    session.add_remote_checksum(frame, checksum)
+                }
+                message => panic!("{message:?} shouldn't be sent during a match"),
+            }
+        }
+    }
+}
+
+// Sends the other side everything they haven't got yet, the same way an online match does.
+fn send_inputs(tick: u32, session: &mut Session, wire: &mut Wire) {
+    let (start, inputs) = session.unacked_inputs();
+
+    wire.send(
+        tick,
+        Message::Inputs {
+            ack: session.remote_frames(),
+            start,
+            inputs: inputs.to_vec(),
+        },
+    );
+
+    for (frame, checksum) in session.take_checksums() {
+        wire.send(tick, Message::Checksum { frame, checksum });
+    }
+}
+
+// Plays both sides with random inputs until every frame has been confirmed on both, and gives back both sessions.
+fn play(latency: u32, loss: u32, delay: u32, seed: u64) -> [Session; 2] {
+    let rules = MatchRules {
+        // Long enough that the match doesn't end before the frames run out.
+        best_of: 99,
+        // Power-ups use the game's randomness too, so they're on to check that stays in sync.
+        power_ups: true,
+        ..MatchRules::default()
+    };
+
+    let mut sessions = [Player::One, Player::Two]
+        .map(|player| Session::new(net::new_game(WIDTH, HEIGHT, rules, seed), player, delay));
+    let mut wires = [
+        Wire::new(latency, loss, seed + 1),
+        Wire::new(latency, loss, seed + 2),
+    ];
+
+    // Each side keeps moving the same way for a while like a person would, and every so often tries to serve.
+    let mut inputs = StdRng::seed_from_u64(seed + 3);
+    let mut directions = [0; 2];
+
+    for tick in 0.. {
+        assert!(tick < FRAMES * 20, "the match never finished");
+
+        for side in 0..2 {
+            let [wire_1, wire_2] = &mut wires;
+            let (incoming, outgoing) = if side == 0 {
+                (wire_2, wire_1)
+            } else {
+                (wire_1, wire_2)
+            };
+            let session = &mut sessions[side];
+
+            incoming.deliver(tick, session);
+
+            if session.frame() < FRAMES && session.can_advance() {
+                if inputs.gen_range(0..4) == 0 {
+                    directions[side] = inputs.gen_range(-1..=1);
+                }
+
+                session.advance(Input {
+                    direction: directions[side],
+                    serve: inputs.gen_range(0..10) == 0,
+                });
+            }
+
+            send_inputs(tick, session, outgoing);
+        }
+
+        if sessions
+            .iter()
+            .all(|session| session.confirmed_frame() >= FRAMES && session.all_acked())
+        {
+            break;
+        }
+    }
+
+    sessions
+}
+
+fn assert_same_game(sessions: &[Session; 2]) {
+    for session in sessions {
+        assert_eq!(session.frame(), FRAMES);
+        assert!(!session.desynced);
+    }
+
+    assert_eq!(sessions[0].game().checksum(), sessions[1].game().checksum());
+}
+
+#[test]
+fn delay_longer_than_latency_never_goes_back() {
+    // Every input gets to the other side before it's played, so nothing ever has to be guessed.
+    let sessions = play(1, 0, 2, 1);
+
+    assert_same_game(&sessions);
+    assert!(sessions.iter().all(|session| session.rollbacks == 0));
+}
+
+#[test]
+fn late_inputs_roll_back_to_the_same_game() {
+    let sessions = play(5, 0, 0, 2);
+
+    assert_same_game(&sessions);
+    assert!(sessions.iter().any(|session| session.rollbacks > 0));
+}
+
+#[test]
+fn lost_inputs_roll_back_to_the_same_game() {
+    for seed in 3..8 {
+        let sessions = play(4, 20, 2, seed);
+
+        assert_same_game(&sessions);
+        assert!(sessions.iter().any(|session| session.rollbacks > 0));
+    }
+}
+
+#[test]
+fn mismatched_checksums_are_noticed() {
+    let rules = MatchRules::default();
+    let mut session = Session::new(net::new_game(WIDTH, HEIGHT, rules, 9), Player::One, 0);
+
+    session.advance(Input::default());
+    session.add_remote_inputs(0, &[Input::default()]);
+
+    // The first frame always gets checksummed.
+    let (frame, checksum) = session.take_checksums()[0];
+
+    assert_eq!(frame, 0);
+    assert!(!session.desynced);
+
+    session.add_remote_checksum(frame, checksum ^ 1);
+
+    assert!(session.desynced);
+}
@@ -0,0 +1,121 @@
+// Checks how games and matches are won, including deuce, where someone has to get ahead by two.
+
+use pong::{
+    game::{Ball, Game, Player},
+    rules::MatchRules,
+};
+
+#[test]
+fn first_to_the_points_wins_without_deuce() {
+    let rules = MatchRules {
+        points_to_win: 5,
+        win_by: 1,
+        ..MatchRules::default()
+    };
+
+    assert!(!rules.won_game(4, 0));
+    assert!(rules.won_game(5, 0));
+    assert!(rules.won_game(5, 4));
+    assert!(!rules.won_game(4, 5));
+}
+
+#[test]
+fn deuce_needs_two_points_ahead() {
+    let rules = MatchRules {
+        points_to_win: 11,
+        win_by: 2,
+        ..MatchRules::default()
+    };
+
+    assert!(rules.won_game(11, 9));
+    assert!(!rules.won_game(11, 10));
+
+    // Past the points to win it keeps going for as long as it takes.
+    assert!(!rules.won_game(12, 11));
+    assert!(rules.won_game(13, 11));
+    assert!(!rules.won_game(30, 29));
+    assert!(rules.won_game(31, 29));
+
+    // Getting ahead by two isn't enough before the points to win.
+    assert!(!rules.won_game(10, 8));
+    assert!(!rules.won_game(2, 0));
+}
+
+#[test]
+fn a_majority_of_games_wins_the_match() {
+    for (best_of, games) in [(1, 1), (3, 2), (5, 3), (7, 4), (4, 3)] {
+        let rules = MatchRules {
+            best_of,
+            ..MatchRules::default()
+        };
+
+        assert_eq!(rules.games_to_win(), games, "best of {best_of}");
+    }
+}
+
+// Plays a point by sending a ball straight past the loser's paddle.
+fn lose_point(game: &mut Game, loser: Player) {
+    let paddle = game.paddle(loser).position;
+    let size = game.paddle_size(loser) as f32;
+    let y = if paddle > game.height as f32 / 2.0 {
+        paddle - size - 3.0
+    } else {
+        paddle + size + 3.0
+    };
+    let (x, velocity_x) = match loser {
+        Player::One => (3.0, -4.0),
+        _ => (game.width as f32 - 4.0, 4.0),
+    };
+
+    game.serving = false;
+    game.balls = vec![Ball {
+        x,
+        y,
+        velocity_x,
+        velocity_y: 0.0,
+        owner: None,
+    }];
+    game.tick();
+}
+
+#[test]
+fn matches_go_to_deuce_and_end_after_enough_games() {
+    let rules = MatchRules {
+        points_to_win: 3,
+        win_by: 2,
+        best_of: 3,
+        ..MatchRules::default()
+    };
+    let mut game = Game::with_size(80, 24, rules, 0);
+
+    // 2-2, then 3-2 isn't enough, then back to 3-3 and on to 5-3.
+    for loser in [
+        Player::Two,
+        Player::Two,
+        Player::One,
+        Player::One,
+        Player::Two,
+    ] {
+        lose_point(&mut game, loser);
+    }
+
+    assert_eq!((game.score_1, game.score_2), (3, 2));
+    assert_eq!(game.games_1, 0);
+
+    lose_point(&mut game, Player::One);
+    lose_point(&mut game, Player::Two);
+    lose_point(&mut game, Player::Two);
+
+    // Winning the game starts the next one from nothing.
+    assert_eq!((game.games_1, game.games_2), (1, 0));
+    assert_eq!((game.score_1, game.score_2), (0, 0));
+    assert_eq!(game.winner, None);
+
+    for _ in 0..3 {
+        lose_point(&mut game, Player::Two);
+    }
+
+    assert_eq!((game.games_1, game.games_2), (2, 0));
+    assert_eq!(game.winner, Some(Player::One));
+    assert_eq!((game.points_1, game.points_2), (8, 3));
+}
@@ -0,0 +1,147 @@
+// Checks who serves each rally, and that the ball waits at the server's paddle until it's served one way or another.
+
+use pong::{
+    game::{Ball, Game, Player},
+    rules::{MatchRules, ServeRule},
+    MAX_SERVE_SLOPE, SERVE_TIMEOUT,
+};
+
+const WIDTH: usize = 80;
+const HEIGHT: usize = 24;
+
+fn game_with_serve(serve: ServeRule, seed: u64) -> Game {
+    let rules = MatchRules {
+        serve,
+        ..MatchRules::default()
+    };
+    let mut game = Game::with_size(WIDTH, HEIGHT, rules, seed);
+
+    game.ball_initial_pos();
+    game
+}
+
+// Plays a point by sending a ball out on the loser's side, well away from their paddle.
+fn lose_point(game: &mut Game, loser: Player) {
+    let (x, velocity_x) = match loser {
+        Player::One => (0.5, -1.0),
+        _ => (WIDTH as f32 - 1.5, 1.0),
+    };
+
+    game.serving = false;
+    game.balls = vec![Ball {
+        x,
+        y: 2.0,
+        velocity_x,
+        velocity_y: 0.0,
+        owner: None,
+    }];
+    game.tick();
+}
+
+// Ticks until the countdown's over and the server can serve.
+fn count_down(game: &mut Game) {
+    for _ in 0..game.rules.serve_delay {
+        assert!(!game.can_serve(game.server));
+
+        game.tick();
+    }
+
+    assert!(game.can_serve(game.server));
+}
+
+#[test]
+fn serves_alternate() {
+    let mut game = game_with_serve(ServeRule::Alternate, 0);
+    let first = game.server;
+
+    for (loser, server) in [
+        (Player::One, first.other()),
+        (Player::One, first),
+        (Player::Two, first.other()),
+        (Player::Two, first),
+    ] {
+        lose_point(&mut game, loser);
+
+        assert_eq!(game.server, server);
+        assert!(game.serving);
+    }
+}
+
+#[test]
+fn the_loser_serves() {
+    let mut game = game_with_serve(ServeRule::Loser, 0);
+
+    for loser in [Player::One, Player::One, Player::Two, Player::One] {
+        lose_point(&mut game, loser);
+
+        assert_eq!(game.server, loser);
+    }
+}
+
+#[test]
+fn the_ball_waits_at_the_servers_paddle() {
+    let mut game = game_with_serve(ServeRule::Alternate, 0);
+    let server = game.server;
+
+    // Trying to serve during the countdown doesn't do anything, and the ball moves along with the paddle.
+    for _ in 0..game.rules.serve_delay {
+        game.serve(server);
+        game.push_paddle(server, 1);
+        game.tick();
+
+        let ball = game.balls[0];
+
+        assert!(game.serving);
+        assert_eq!(ball.x, game.paddle_face(server));
+        assert_eq!(ball.y, game.paddle(server).position);
+    }
+
+    // The other player can't serve for them either.
+    game.serve(server.other());
+
+    assert!(game.serving);
+
+    game.serve(server);
+
+    assert!(!game.serving);
+    assert_eq!(game.balls[0].owner, Some(server));
+}
+
+#[test]
+fn serves_go_across_the_court_at_a_gentle_slope() {
+    for seed in 0..50 {
+        let mut game = game_with_serve(ServeRule::Alternate, seed);
+        let server = game.server;
+
+        count_down(&mut game);
+        game.serve(server);
+
+        let ball = game.balls[0];
+        let towards = match server {
+            Player::One => 1.0,
+            _ => -1.0,
+        };
+
+        assert_eq!(ball.velocity_x.signum(), towards);
+        assert!((ball.velocity_y / ball.velocity_x).abs() <= MAX_SERVE_SLOPE);
+    }
+}
+
+#[test]
+fn the_ball_gets_served_if_the_server_waits_too_long() {
+    let mut game = game_with_serve(ServeRule::Alternate, 0);
+    let server = game.server;
+
+    count_down(&mut game);
+
+    for _ in 0..SERVE_TIMEOUT {
+        game.tick();
+
+        assert!(game.serving);
+    }
+
+    game.tick();
+
+    assert!(!game.serving);
+    assert_eq!(game.balls[0].owner, Some(server));
+}
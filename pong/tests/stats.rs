@@ -0,0 +1,71 @@
+// Checks the stats that get saved for a match come out exactly as expected, since other tools read them.
+
+use pong::{
+    game::{Game, Player},
+    rules::MatchRules,
+    stats::{quote, to_json},
+    Mode, Seat,
+};
+
+// A match that player 1 won two games to one, with a few rallies played.
+fn finished_match() -> Game {
+    let mut game = Game::with_size(80, 24, MatchRules::default(), 0);
+
+    game.winner = Some(Player::One);
+    game.points_1 = 30;
+    game.points_2 = 25;
+    game.games_1 = 2;
+    game.games_2 = 1;
+    game.rallies = vec![3, 10, 2];
+
+    game.stats.hits[0] = [1, 2, 3, 2, 1];
+    game.stats.hits[1] = [0, 4, 1, 0, 2];
+    game.stats.served = [28, 27, 0, 0];
+    game.stats.won_on_serve = [15, 12, 0, 0];
+    // In tiles a tick, which is 60 ms.
+    game.stats.top_speed = 1.5;
+
+    game
+}
+
+#[test]
+fn two_player_matches_are_saved_with_points_and_games() {
+    assert_eq!(
+        to_json(&finished_match(), Mode::TwoPlayer, 1_700_000_000),
+        concat!(
+            r#"{"finished":1700000000,"winner":"Player 1","players":["#,
+            r#"{"name":"Player 1","points":30,"games":2,"served":28,"won_on_serve":15,"hits":[1, 2, 3, 2, 1]},"#,
+            r#"{"name":"Player 2","points":25,"games":1,"served":27,"won_on_serve":12,"hits":[0, 4, 1, 0, 2]}"#,
+            r#"],"rallies":3,"longest_rally":10,"average_rally":5.00,"top_speed":25.00}"#,
+        )
+    );
+}
+
+#[test]
+fn free_for_alls_are_saved_with_lives() {
+    let mut game = Game::with_size(80, 24, MatchRules::default(), 0);
+    let seats = [Seat::Human, Seat::Cpu, Seat::Empty, Seat::Cpu];
+
+    game.lives = Some([2, 0, 0, 1]);
+    game.winner = None;
+
+    assert_eq!(
+        to_json(&game, Mode::FreeForAll(seats), 0),
+        concat!(
+            r#"{"finished":0,"winner":null,"players":["#,
+            r#"{"name":"Player 1","lives":2,"served":0,"won_on_serve":0,"hits":[0, 0, 0, 0, 0]},"#,
+            r#"{"name":"CPU 2","lives":0,"served":0,"won_on_serve":0,"hits":[0, 0, 0, 0, 0]},"#,
+            r#"{"name":"CPU 4","lives":1,"served":0,"won_on_serve":0,"hits":[0, 0, 0, 0, 0]}"#,
+            r#"],"rallies":0,"longest_rally":0,"average_rally":0.00,"top_speed":0.00}"#,
+        )
+    );
+}
+
+#[test]
+fn strings_are_escaped() {
+    assert_eq!(quote("Host"), r#""Host""#);
+    assert_eq!(quote(r#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(quote(r"back\slash"), r#""back\\slash""#);
+    assert_eq!(quote("tab\there\n"), r#""tab\u0009here\u000a""#);
+    assert_eq!(quote("héllo ♥"), "\"héllo ♥\"");
+}
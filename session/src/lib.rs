@@ -0,0 +1,66 @@
+// What pong, flappy bird and food catcher share for running in a terminal and then handing it back.
+// None of them exit the process when they're done, so something like the launcher can run one after another.
+
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+use console::{Key, Term};
+
+// Why a game stopped running.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EndReason {
+    Quit,
+    GameOver,
+}
+
+// What's handed back once a game is over, so whoever started it can decide what to do next.
+#[derive(Clone, Copy, Debug)]
+pub struct GameResult {
+    pub score: u32,
+    pub reason: EndReason,
+}
+
+// Messages that can be sent to a render thread.
+pub enum ChannelMessage {
+    Stop,
+}
+
+// Gathers input in a separate thread, and passes it on to whichever game is running.
+// Pressing `q` always ends the session, so that's when this thread stops.
+pub fn spawn_input(term: &Term) -> (Receiver<Key>, JoinHandle<()>) {
+    let (key_sender, keys) = mpsc::channel();
+    let input_term = term.clone();
+    let input_thread = thread::spawn(move || loop {
+        let key = input_term
+            .read_key()
+            .expect("an error occurred while reading input");
+
+        let quit = key == Key::Char('q');
+
+        if key_sender.send(key).is_err() || quit {
+            break;
+        }
+    });
+
+    (keys, input_thread)
+}
+
+// Waits for the player to decide whether they want to play again.
+pub fn play_again(keys: &Receiver<Key>) -> bool {
+    for key in keys.iter() {
+        match key {
+            Key::Char('r') => return true,
+            Key::Char('q') => return false,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+// Clears the screen and shows the cursor again, without exiting.
+pub fn cleanup(exit_message: &str) {
+    println!("\r\x1b[2J\r\x1b[H\x1b[?25h{exit_message}");
+}
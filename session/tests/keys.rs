@@ -0,0 +1,43 @@
+use std::sync::mpsc;
+
+use console::Key;
+use session::play_again;
+
+#[test]
+fn r_plays_again() {
+    let (sender, keys) = mpsc::channel();
+
+    sender.send(Key::Char('r')).unwrap();
+
+    assert!(play_again(&keys));
+}
+
+#[test]
+fn q_stops_playing() {
+    let (sender, keys) = mpsc::channel();
+
+    sender.send(Key::Char('q')).unwrap();
+
+    assert!(!play_again(&keys));
+}
+
+#[test]
+fn other_keys_are_ignored() {
+    let (sender, keys) = mpsc::channel();
+
+    for key in [Key::ArrowUp, Key::Char('x'), Key::Enter, Key::Char('r')] {
+        sender.send(key).unwrap();
+    }
+
+    assert!(play_again(&keys));
+}
+
+#[test]
+fn stops_once_the_input_is_gone() {
+    let (sender, keys) = mpsc::channel();
+
+    sender.send(Key::ArrowDown).unwrap();
+    drop(sender);
+
+    assert!(!play_again(&keys));
+}
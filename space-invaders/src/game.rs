@@ -1,6 +1,6 @@
 use std::io;
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 const TICKS_TO_MOVE_INVADERS: usize = 12;
 const INVADER_PADDING: usize = 8;
@@ -162,10 +162,17 @@ pub struct Game {
 
     pub width: usize,
     pub height: usize,
+
+    // The daily challenge's seed, if this is one. Starting over keeps it, so the invaders fire the same way again.
+    pub daily: Option<u64>,
+
+    // Picks which invader shoots next.
+    rng: StdRng,
 }
 
 impl Game {
-    pub fn init(width: usize, height: usize) -> Game {
+    /// Sets up a new game, where the invaders fire in a random pattern unless there's a `daily` seed.
+    pub fn init(width: usize, height: usize, daily: Option<u64>) -> Game {
         let mut invaders = Vec::new();
 
         for i in 0..5 {
@@ -241,6 +248,8 @@ impl Game {
             score: 0,
             width,
             height,
+            daily,
+            rng: StdRng::seed_from_u64(daily.unwrap_or_else(rand::random)),
         };
 
         game
@@ -361,11 +370,9 @@ impl Game {
             }
         }
 
-        let mut rand = rand::thread_rng();
-
-        if rand.gen_range(0..5) == 0 {
-            let i = rand.gen_range(0..self.invaders_group.invaders.len());
-            let j = rand.gen_range(0..self.invaders_group.invaders[i].len());
+        if self.rng.gen_range(0..5) == 0 {
+            let i = self.rng.gen_range(0..self.invaders_group.invaders.len());
+            let j = self.rng.gen_range(0..self.invaders_group.invaders[i].len());
 
             let shooter = &self.invaders_group.invaders[i][j];
 
@@ -10,6 +10,7 @@ use crossterm::execute;
 use image::GenericImageView;
 
 use super::math;
+use crate::spectate::Server;
 
 #[derive(Copy, Clone, Debug)]
 pub struct RGB(pub u8, pub u8, pub u8);
@@ -35,6 +36,9 @@ pub struct Screen {
 
     // The output to write to
     out: std::io::Stdout,
+
+    // Where every rendered frame gets sent, if anyone is watching.
+    spectators: Option<Server>,
 }
 
 impl Screen {
@@ -61,6 +65,7 @@ impl Screen {
             width,
             initial,
             out: io::stdout(),
+            spectators: None,
         };
 
         initial_draw(&mut screen);
@@ -94,6 +99,7 @@ impl Screen {
                 self.reset();
                 action(&mut self);
                 self.render();
+                self.broadcast();
 
                 const TARGET_DELTA: Duration = Duration::from_millis(16);
 
@@ -104,6 +110,25 @@ impl Screen {
         )
     }
 
+    /// Sends every frame rendered by [`Screen::on_update`] from now on to a spectator server.
+    pub fn share(&mut self, server: Server) {
+        self.spectators = Some(server);
+    }
+
+    /// Sends the screen to anyone watching.
+    fn broadcast(&mut self) {
+        if let Some(server) = &mut self.spectators {
+            let pixels: Vec<u8> = self
+                .data
+                .iter()
+                .flatten()
+                .flat_map(|pixel| [pixel.0, pixel.1, pixel.2])
+                .collect();
+
+            server.broadcast(self.width, self.height, &pixels, "");
+        }
+    }
+
     /// Renders the screen.
     pub fn render(&self) {
         // Rushing out the output is actually faster than collecting it and then outputting it, and it leads to less flicker.
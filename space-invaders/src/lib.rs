@@ -2,3 +2,32 @@
 
 pub mod game;
 pub mod gfx;
+pub mod play;
+pub mod spectate;
+
+pub use play::run;
+
+/// The smallest terminal the game can be played in, in columns and rows.
+pub const MIN_WIDTH: u16 = 148;
+pub const MIN_HEIGHT: u16 = 64;
+
+/// Why the game stopped running.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EndReason {
+    Quit,
+    GameOver,
+}
+
+/// What's handed back once the game is over, so whoever started it can decide what to do next.
+#[derive(Clone, Copy, Debug)]
+pub struct GameResult {
+    pub score: u64,
+    pub reason: EndReason,
+}
+
+/// Checks if the terminal is big enough to play in.
+pub fn fits_terminal() -> bool {
+    let (width, height) = crossterm::terminal::size().unwrap();
+
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
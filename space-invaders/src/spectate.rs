@@ -0,0 +1,94 @@
+//! Lets other people watch a game from another terminal. The actual sending and receiving is shared with
+//! the other games, see the broadcast crate, so this is just the space invaders side of it.
+
+use std::{fs, io, net::SocketAddr, sync::mpsc::RecvTimeoutError, time::Duration};
+
+use broadcast::{Frame, Watcher};
+use psf_rs::Font;
+
+use crate::gfx::{
+    input::on_input,
+    screen::{Screen, RGB},
+};
+
+pub use broadcast::Server;
+
+/// Sent as soon as someone connects, so anything that isn't watching space invaders gets turned away straight away.
+const MAGIC: [u8; 4] = *b"SITV";
+
+/// How often the spectator checks for escape while no frames are coming in.
+const KEY_TIME: Duration = Duration::from_millis(100);
+
+/// Starts listening for spectators on an address, which should be on 127.0.0.1 unless they're on other computers.
+pub fn start(addr: SocketAddr) -> io::Result<Server> {
+    Server::start_on(addr, MAGIC)
+}
+
+/// Draws a frame from the game being watched.
+fn draw(screen: &mut Screen, frame: &Frame) {
+    // The game might be bigger than this terminal, in which case the edges get cut off.
+    screen.reset();
+
+    for y in 0..frame.height.min(screen.height) {
+        for x in 0..frame.width.min(screen.width) {
+            let pixel = frame.cell(x, y);
+
+            screen.set_pixel(x, y, RGB(pixel[0], pixel[1], pixel[2]));
+        }
+    }
+
+    screen.render();
+}
+
+/// Watches a game someone is broadcasting at `addr`, until they stop or the spectator presses escape.
+/// The terminal should be checked with [`crate::fits_terminal`] first.
+pub fn watch(addr: &str) -> io::Result<()> {
+    let frames = Watcher::connect(addr, MAGIC)?.spawn();
+    let font =
+        Font::load(&fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/font/font9.psfu")).unwrap());
+
+    let mut screen = Screen::new(
+        |screen| {
+            screen.bg(RGB(0, 0, 0));
+        },
+        "Watching Space Invaders!",
+    );
+
+    // The input thread stops by itself once escape is pressed, there's nothing else to do with keys.
+    let input = on_input(|_| ());
+
+    // Frames are read in their own thread, so escape works straight away even while the game's gone quiet.
+    let result = loop {
+        if input.is_finished() {
+            break Ok(());
+        }
+
+        match frames.recv_timeout(KEY_TIME) {
+            // Anything that isn't made of pixels can't be drawn.
+            Ok(Ok(frame)) if frame.cell_size == 3 => draw(&mut screen, &frame),
+            Ok(Ok(_)) => (),
+            Ok(Err(err)) => break Err(err),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break Ok(()),
+        }
+    };
+
+    if result.is_err() {
+        screen.text(
+            4,
+            4,
+            RGB(255, 255, 255),
+            &font,
+            "The game stopped broadcasting.\nPress escape to quit.",
+        );
+        screen.render();
+
+        input.join().unwrap();
+    }
+
+    match result {
+        // That's just the game closing normally.
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+        result => result,
+    }
+}